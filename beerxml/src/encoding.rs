@@ -0,0 +1,227 @@
+//! # Encoding
+//!
+//! Reading and writing BeerXML documents in other encodings than UTF-8.
+//!
+//! The BeerXML spec examples declare `encoding="ISO-8859-1"`,
+//! so files are decoded according to their byte order mark or XML declaration
+//! before parsing. Files without either that are not valid UTF-8 are read as ISO-8859-1,
+//! as many older brewing programs wrote them. Documents are always written as UTF-8.
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8, WINDOWS_1252};
+use serde::{de::DeserializeOwned, Serialize};
+use std::fmt;
+use std::io::Read;
+
+/// Declaration of the decoded contents and of every written document
+pub const UTF8_DECLARATION: &str = r#"<?xml version="1.0" encoding="UTF-8"?>"#;
+
+#[derive(Debug)]
+pub enum Error {
+    Io(std::io::Error),
+    /// The declared encoding is not known
+    UnknownEncoding(String),
+    /// The contents are not valid in the encoding
+    Malformed(&'static str),
+    Xml(serde_xml_rs::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(err) => write!(f, "{}", err),
+            Error::UnknownEncoding(label) => write!(f, "Unknown encoding '{}'", label),
+            Error::Malformed(encoding) => write!(f, "Invalid {} contents", encoding),
+            Error::Xml(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl From<serde_xml_rs::Error> for Error {
+    fn from(err: serde_xml_rs::Error) -> Self {
+        Error::Xml(err)
+    }
+}
+
+/// Encoding of `bytes`, from the byte order mark or the XML declaration
+///
+/// Defaults to UTF-8, returns the length of the byte order mark.
+pub fn detect(bytes: &[u8]) -> Result<(&'static Encoding, usize), Error> {
+    if let Some(bom) = Encoding::for_bom(bytes) {
+        return Ok(bom);
+    }
+    // UTF-16 without byte order mark, starting with "<?"
+    if bytes.starts_with(&[b'<', 0, b'?', 0]) {
+        return Ok((UTF_16LE, 0));
+    }
+    if bytes.starts_with(&[0, b'<', 0, b'?']) {
+        return Ok((UTF_16BE, 0));
+    }
+    match declared_encoding(bytes) {
+        Some(label) => Encoding::for_label(label.as_bytes())
+            .map(|encoding| (encoding, 0))
+            .ok_or(Error::UnknownEncoding(label)),
+        None => Ok((UTF_8, 0)),
+    }
+}
+
+/// Decodes `bytes` to a string
+///
+/// The encoding in the XML declaration is replaced with UTF-8,
+/// to match the decoded contents.
+pub fn decode(bytes: &[u8]) -> Result<String, Error> {
+    let (encoding, bom_len) = detect(bytes)?;
+    let undeclared = bom_len == 0 && encoding == UTF_8 && declared_encoding(bytes).is_none();
+    let bytes = &bytes[bom_len..];
+    let contents = match encoding.decode_without_bom_handling_and_without_replacement(bytes) {
+        Some(contents) => contents,
+        // ISO-8859-1 is decoded as its superset windows-1252, like a declared ISO-8859-1
+        None if undeclared => WINDOWS_1252.decode_without_bom_handling(bytes).0,
+        None => return Err(Error::Malformed(encoding.name())),
+    };
+    Ok(with_utf8_declaration(&contents))
+}
+
+/// Parses a document in any supported encoding
+pub fn from_bytes<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, Error> {
+    Ok(serde_xml_rs::from_str(&decode(bytes)?)?)
+}
+
+/// Reads and parses a document in any supported encoding
+pub fn from_reader<T: DeserializeOwned, R: Read>(mut reader: R) -> Result<T, Error> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+    from_bytes(&bytes)
+}
+
+/// Writes `value` as a UTF-8 document, with an XML declaration
+pub fn to_string<T: Serialize>(value: &T) -> Result<String, Error> {
+    let xml = serde_xml_rs::to_string(value)?;
+    Ok(format!("{}\n{}", UTF8_DECLARATION, xml))
+}
+
+/// Value of the `encoding` attribute in the XML declaration
+fn declared_encoding(bytes: &[u8]) -> Option<String> {
+    let declaration = xml_declaration(&String::from_utf8_lossy(bytes))?.to_string();
+    let value = &declaration[declaration.find("encoding")? + "encoding".len()..];
+    let value = value.trim_start().strip_prefix('=')?.trim_start();
+    let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let value = &value[1..];
+    Some(value[..value.find(quote)?].to_string())
+}
+
+fn xml_declaration(contents: &str) -> Option<&str> {
+    if contents.starts_with("<?xml") {
+        contents.find("?>").map(|end| &contents[..end + 2])
+    } else {
+        None
+    }
+}
+
+fn with_utf8_declaration(contents: &str) -> String {
+    match xml_declaration(contents) {
+        Some(declaration) if declaration.contains("encoding") => {
+            format!("{}{}", UTF8_DECLARATION, &contents[declaration.len()..])
+        }
+        _ => contents.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOP: &str = "<HOP>
+  <NAME>Saaz från Žatec</NAME>
+  <VERSION>1</VERSION>
+  <ALPHA>3.5</ALPHA>
+  <AMOUNT>0.050</AMOUNT>
+  <USE>Boil</USE>
+  <TIME>15</TIME>
+</HOP>";
+
+    #[test]
+    fn iso_8859_1() {
+        let xml = format!(
+            "<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?>\n{}",
+            HOP.replace('Ž', "Z")
+        );
+        let (bytes, _, _) = encoding_rs::WINDOWS_1252.encode(&xml);
+        assert!(std::str::from_utf8(&bytes).is_err());
+        let hop: crate::Hop = from_bytes(&bytes).unwrap();
+        assert_eq!(hop.name, "Saaz från Zatec");
+    }
+
+    #[test]
+    fn windows_1252() {
+        let xml = format!("<?xml version='1.0' encoding='windows-1252'?>\n{}", HOP);
+        let (bytes, _, _) = encoding_rs::WINDOWS_1252.encode(&xml);
+        let hop: crate::Hop = from_bytes(&bytes).unwrap();
+        assert_eq!(hop.name, "Saaz från Žatec");
+    }
+
+    #[test]
+    fn utf_16() {
+        let xml = format!("<?xml version=\"1.0\" encoding=\"UTF-16\"?>\n{}", HOP);
+        let le: Vec<u8> = xml.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        let hop: crate::Hop = from_bytes(&le).unwrap();
+        assert_eq!(hop.name, "Saaz från Žatec");
+        let be: Vec<u8> = "\u{feff}"
+            .encode_utf16()
+            .chain(xml.encode_utf16())
+            .flat_map(u16::to_be_bytes)
+            .collect();
+        let hop: crate::Hop = from_bytes(&be).unwrap();
+        assert_eq!(hop.name, "Saaz från Žatec");
+    }
+
+    #[test]
+    fn unknown_encoding() {
+        let xml = format!("<?xml version=\"1.0\" encoding=\"EBCDIC\"?>\n{}", HOP);
+        assert!(matches!(
+            decode(xml.as_bytes()),
+            Err(Error::UnknownEncoding(_))
+        ));
+    }
+
+    #[test]
+    fn write_utf_8() {
+        #[derive(Serialize, serde::Deserialize)]
+        #[serde(rename = "NOTE")]
+        struct Note {
+            #[serde(rename = "NAME")]
+            name: String,
+        }
+        let written = to_string(&Note {
+            name: "Blåbär öl".into(),
+        })
+        .unwrap();
+        assert!(written.starts_with(UTF8_DECLARATION));
+        assert!(written.contains("<NAME>Blåbär öl</NAME>"));
+        assert_eq!(decode(written.as_bytes()).unwrap(), written);
+        let note: Note = from_bytes(written.as_bytes()).unwrap();
+        assert_eq!(note.name, "Blåbär öl");
+    }
+
+    #[test]
+    fn undeclared_iso_8859_1() {
+        let xml = HOP.replace('Ž', "Z");
+        let (bytes, _, _) = encoding_rs::WINDOWS_1252.encode(&xml);
+        assert!(std::str::from_utf8(&bytes).is_err());
+        let hop: crate::Hop = from_bytes(&bytes).unwrap();
+        assert_eq!(hop.name, "Saaz från Zatec");
+        // A declared UTF-8 document is not guessed
+        let declared = [
+            r#"<?xml version="1.0" encoding="UTF-8"?>"#.as_bytes(),
+            &bytes,
+        ]
+        .concat();
+        assert!(matches!(decode(&declared), Err(Error::Malformed(_))));
+    }
+}
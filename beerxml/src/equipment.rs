@@ -14,15 +14,15 @@ pub struct Equipment {
     version: u8,
     ///The pre-boil volume used in this particular instance for this equipment setup.
     ///Note that this may be a calculated value depending on the `calc_boil_volume` parameter.
-    boil_size: f32,
+    pub boil_size: Liters,
     ///The target volume of the batch at the start of fermentation.
     batch_size: f32,
     ///Volume of the mash tun in liters.
-    tun_volume: Option<Liters>,
+    pub tun_volume: Option<Liters>,
     ///Weight of the mash tun in kilograms.
     ///Used primarily to calculate the thermal parameters of
     ///the mash tun – in conjunction with the volume and specific heat.
-    tun_weight: Option<f32>,
+    pub tun_weight: Option<Kilograms>,
     ///Cal/(gram deg C)
     tun_specific_heat: Option<f32>,
     ///The amount of top up water normally added just prior to starting fermentation.
@@ -41,10 +41,14 @@ pub struct Equipment {
     #[serde(deserialize_with = "utils::opt_bool_de_from_str")]
     calc_boil_volume: Option<bool>,
     ///Amount lost to the lauter tun and equipment associated with the lautering process.
-    lauter_deadspace: Option<Liters>,
+    pub lauter_deadspace: Option<Liters>,
     ///Amount normally added to the boil kettle before the boil.
     top_up_kettle: Option<Liters>,
-    hop_utilization: Option<Percent>,
+    ///Large batch hop utilization.
+    ///
+    ///This value should be 100% for batches less than 20 gallons,
+    ///but may be higher (200% or more) for very large batch equipment.
+    pub hop_utilization: Option<Percent>,
     notes: Option<String>,
 }
 
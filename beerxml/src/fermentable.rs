@@ -5,41 +5,75 @@ use crate::utils;
 use brew_calculator::units::*;
 use serde::{Deserialize, Deserializer};
 
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Deserialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "UPPERCASE")]
 pub struct Fermentable {
-    name: String,
+    pub name: String,
     version: u8,
     #[serde(rename = "TYPE")]
-    type_: Type,
+    pub type_: Type,
     ///Weight in kilograms.
-    amount: Kilograms,
+    pub amount: Kilograms,
+    ///Percent dry yield (fine grain) for the grain, or the raw yield by weight if this is an
+    ///extract adjunct or sugar.
     #[serde(rename = "YIELD")]
-    yield_: Percent,
+    pub yield_: Percent,
     ///The color of the item in Lovibond Units (SRM for liquid extracts).
-    color: f32,
+    pub color: f32,
     #[serde(default)]
     #[serde(deserialize_with = "utils::opt_bool_de_from_str")]
     ///May be `true` if this item is normally added after the boil.
     ///
     ///The default value is `false` since most grains are added during the mash or boil.
-    add_after_boil: Option<bool>,
-    origin: Option<String>,
-    supplier: Option<String>,
+    pub add_after_boil: Option<bool>,
+    pub origin: Option<String>,
+    pub supplier: Option<String>,
     notes: Option<String>,
     ///Percent difference between the coarse grain yield and fine grain yield.
     ///
     ///Only appropriate for a "Grain" or "Adjunct" type, otherwise this value is ignored.
-    coarse_fine_diff: Option<Percent>,
-    moisture: Option<Percent>,
+    pub coarse_fine_diff: Option<Percent>,
+    ///Percent moisture in the grain.
+    pub moisture: Option<Percent>,
     ///The diastatic power of the grain as measured in "Lintner" units.
-    diastatic_power: Option<f32>,
-    protein: Option<Percent>,
-    max_in_batch: Option<Percent>,
+    pub diastatic_power: Option<f32>,
+    ///The percent protein in the grain.
+    pub protein: Option<Percent>,
+    ///The recommended maximum percentage (by weight) this ingredient should represent in a batch of beer.
+    pub max_in_batch: Option<Percent>,
     #[serde(default)]
     #[serde(deserialize_with = "utils::opt_bool_de_from_str")]
-    recommend_mash: Option<bool>,
+    ///`true` if it is recommended the grain be mashed, `false` if it can be steeped.
+    pub recommend_mash: Option<bool>,
     ibu_gal_per_lb: Option<f32>,
+    ///Amount in inventory as a display string with units, e.g. "2.5 lb".
+    pub inventory: Option<String>,
+}
+
+impl Fermentable {
+    ///Fermentable with only the required fields set.
+    pub fn new(name: String, type_: Type, amount: Kilograms, yield_: Percent, color: f32) -> Self {
+        Self {
+            name,
+            version: 1,
+            type_,
+            amount,
+            yield_,
+            color,
+            add_after_boil: None,
+            origin: None,
+            supplier: None,
+            notes: None,
+            coarse_fine_diff: None,
+            moisture: None,
+            diastatic_power: None,
+            protein: None,
+            max_in_batch: None,
+            recommend_mash: None,
+            ibu_gal_per_lb: None,
+            inventory: None,
+        }
+    }
 }
 
 #[derive(Deserialize, Debug, PartialEq, Default)]
@@ -49,8 +83,8 @@ pub struct Fermentables {
     pub fermentable: Vec<Fermentable>,
 }
 
-#[derive(Debug, PartialEq)]
-enum Type {
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Type {
     Grain,
     Sugar,
     Extract,
@@ -58,6 +92,13 @@ enum Type {
     Adjunct,
 }
 
+impl Type {
+    ///Items that are mashed or steeped and whose yield depends on the brewhouse efficiency.
+    pub fn mashed(self) -> bool {
+        self == Type::Grain || self == Type::Adjunct
+    }
+}
+
 impl<'de> Deserialize<'de> for Type {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -111,6 +152,7 @@ mod beerxml {
             max_in_batch: None,
             recommend_mash: None,
             ibu_gal_per_lb: None,
+            inventory: None,
         };
         assert_eq!(parsed_fermentable, true_fermentable);
     }
@@ -149,6 +191,7 @@ mod beerxml {
             max_in_batch: None,
             recommend_mash: None,
             ibu_gal_per_lb: Some(16.6),
+            inventory: None,
         };
         assert_eq!(parsed_fermentable, true_fermentable);
     }
@@ -200,6 +243,7 @@ mod beerxml {
             max_in_batch: Some(10.0),
             recommend_mash: None,
             ibu_gal_per_lb: None,
+            inventory: None,
         };
         assert_eq!(parsed_fermentable, true_fermentable);
     }
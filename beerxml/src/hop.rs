@@ -2,6 +2,7 @@
 use brew_calculator::units::*;
 use serde::Deserialize;
 use serde::Deserializer;
+use std::fmt;
 
 #[derive(Deserialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "UPPERCASE")]
@@ -17,27 +18,58 @@ pub struct Hop {
     notes: Option<String>,
     #[serde(rename = "TYPE")]
     type_: Option<Type>,
-    form: Option<Form>,
-    beta: Option<Percent>,
+    pub form: Option<Form>,
+    pub beta: Option<Percent>,
     ///Hop Stability Index
     ///
     ///Defined as the percentage of hop alpha lost in 6 months of storage
-    hsi: Option<Percent>,
-    origin: Option<String>,
-    substitutes: Option<String>,
-    humulene: Option<Percent>,
-    caryophyllene: Option<Percent>,
-    cohumulone: Option<Percent>,
-    myrcene: Option<Percent>,
+    pub hsi: Option<Percent>,
+    pub origin: Option<String>,
+    ///Substitutes that can be used for this hop, as free text.
+    pub substitutes: Option<String>,
+    ///Humulene level in percent.
+    pub humulene: Option<Percent>,
+    ///Caryophyllene level in percent.
+    pub caryophyllene: Option<Percent>,
+    ///Cohumulone level in percent.
+    pub cohumulone: Option<Percent>,
+    ///Myrcene level in percent.
+    pub myrcene: Option<Percent>,
+    ///Amount in inventory as a display string with units, e.g. "2.5 lb".
+    pub inventory: Option<String>,
 }
 
 impl Hop {
+    ///Hop with only the required fields set.
+    pub fn new(name: String, alpha: Percent, amount: Kilograms, use_: Use, time: Minutes) -> Self {
+        Self {
+            name,
+            version: 1,
+            alpha,
+            amount,
+            use_,
+            time,
+            notes: None,
+            type_: None,
+            form: None,
+            beta: None,
+            hsi: None,
+            origin: None,
+            substitutes: None,
+            humulene: None,
+            caryophyllene: None,
+            cohumulone: None,
+            myrcene: None,
+            inventory: None,
+        }
+    }
+
     pub fn bittering(&self) -> bool {
         self.use_ != Use::Aroma && self.use_ != Use::DryHop
     }
 }
 
-#[derive(Copy, Debug, PartialEq, Clone)]
+#[derive(Copy, Debug, PartialEq, Eq, Hash, Clone)]
 pub enum Use {
     Boil,
     DryHop,
@@ -70,6 +102,20 @@ impl<'de> Deserialize<'de> for Use {
     }
 }
 
+/// BeerXML name of the use, e.g. "Dry Hop"
+impl fmt::Display for Use {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Use::Boil => "Boil",
+            Use::DryHop => "Dry Hop",
+            Use::Mash => "Mash",
+            Use::FirstWort => "First Wort",
+            Use::Aroma => "Aroma",
+        };
+        f.write_str(name)
+    }
+}
+
 #[derive(Deserialize, Debug, PartialEq, Clone)]
 enum Type {
     Bittering,
@@ -77,8 +123,8 @@ enum Type {
     Both,
 }
 
-#[derive(Deserialize, Debug, PartialEq, Clone)]
-enum Form {
+#[derive(Deserialize, Copy, Debug, PartialEq, Clone)]
+pub enum Form {
     Pellet,
     Plug,
     Leaf,
@@ -119,6 +165,7 @@ mod beerxml {
             caryophyllene: None,
             cohumulone: None,
             myrcene: None,
+            inventory: None,
         };
         assert_eq!(parsed_hop, true_hop);
     }
@@ -153,6 +200,7 @@ mod beerxml {
             caryophyllene: None,
             cohumulone: None,
             myrcene: None,
+            inventory: None,
         };
         assert_eq!(parsed_hop, true_hop);
     }
@@ -205,6 +253,7 @@ mod beerxml {
             caryophyllene: None,
             cohumulone: Some(13.2),
             myrcene: Some(24.4),
+            inventory: None,
         };
         assert_eq!(parsed_hop, true_hop);
     }
@@ -1,3 +1,4 @@
+pub mod encoding;
 pub mod equipment;
 pub mod fermentable;
 pub mod hop;
@@ -6,6 +7,7 @@ pub mod misc;
 pub mod recipe;
 pub mod style;
 mod utils;
+pub mod validation;
 pub mod water;
 pub mod yeast;
 
@@ -14,6 +16,7 @@ pub use fermentable::Fermentable;
 pub use hop::Hop;
 pub use misc::Misc;
 pub use recipe::Recipe;
+pub use recipe::Recipes;
 pub use recipe::Type;
 pub use style::Style;
 pub use water::Water;
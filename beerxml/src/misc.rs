@@ -6,26 +6,49 @@ use crate::utils;
 use brew_calculator::units::*;
 use serde::Deserialize;
 use serde::Deserializer;
+use std::fmt;
 
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Deserialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "UPPERCASE")]
 pub struct Misc {
-    name: String,
+    pub name: String,
     version: u8,
     #[serde(rename = "TYPE")]
-    type_: Type,
+    pub type_: Type,
     #[serde(rename = "USE")]
-    use_: Use,
+    pub use_: Use,
     ///Amount of time in minutes.
-    time: Minutes,
-    ///Amount of item used. The default measurements are by weight, but this may be the
-    ///measurement in volume units if `amount_is_weight` is set to `true`
-    amount: f32,
+    pub time: Minutes,
+    ///Amount of item used, measured in liters. If the flag `amount_is_weight` is set to `true`
+    ///then this measurement is in kilograms and not liters.
+    pub amount: f32,
+    ///If `true`, the amount is in kilograms instead of liters.
     #[serde(default)]
     #[serde(deserialize_with = "utils::opt_bool_de_from_str")]
-    amount_is_weight: Option<bool>,
-    use_for: Option<String>,
+    pub amount_is_weight: Option<bool>,
+    ///Short description of what the ingredient is used for.
+    pub use_for: Option<String>,
     notes: Option<String>,
+    ///Amount in inventory as a display string with units, e.g. "2.5 lb".
+    pub inventory: Option<String>,
+}
+
+impl Misc {
+    ///Misc with only the required fields set.
+    pub fn new(name: String, type_: Type, use_: Use, time: Minutes, amount: f32) -> Self {
+        Self {
+            name,
+            version: 1,
+            type_,
+            use_,
+            time,
+            amount,
+            amount_is_weight: None,
+            use_for: None,
+            notes: None,
+            inventory: None,
+        }
+    }
 }
 
 #[derive(Deserialize, Debug, PartialEq, Default)]
@@ -35,8 +58,8 @@ pub struct Miscs {
     pub misc: Vec<Misc>,
 }
 
-#[derive(Deserialize, Debug, PartialEq)]
-enum Use {
+#[derive(Deserialize, Copy, Clone, Debug, PartialEq)]
+pub enum Use {
     Boil,
     Mash,
     Primary,
@@ -44,8 +67,22 @@ enum Use {
     Bottling,
 }
 
-#[derive(Debug, PartialEq)]
-enum Type {
+/// BeerXML name of the use
+impl fmt::Display for Use {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Use::Boil => "Boil",
+            Use::Mash => "Mash",
+            Use::Primary => "Primary",
+            Use::Secondary => "Secondary",
+            Use::Bottling => "Bottling",
+        };
+        f.write_str(name)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Type {
     Spice,
     Fining,
     WaterAgent,
@@ -100,6 +137,7 @@ mod beerxml {
             amount_is_weight: None,
             use_for: None,
             notes: None,
+            inventory: None,
         };
         assert_eq!(parsed_misc, true_misc);
     }
@@ -133,6 +171,7 @@ mod beerxml {
             notes: Some(
                 "Used in Belgian Wit, Whites, and Holiday ales.  Very good when used in light wheat ales.  Often used with Bitter Orange Peel. Crack open seeds and add at the end of the boil to extract aroma and flavor."
                 .into()),
+            inventory: None,
         };
         assert_eq!(parsed_misc, true_misc);
     }
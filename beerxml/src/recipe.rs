@@ -59,7 +59,17 @@ pub struct Recipe {
     pub ibu_method: Option<ibu::Method>,
 }
 
-#[derive(Debug, PartialEq)]
+///Wrapper type for a list of recipes
+///
+///A BeerXML file may contain several recipes enclosed in a `<RECIPES>` tag.
+#[derive(Deserialize, Debug, PartialEq, Default)]
+#[serde(rename_all = "UPPERCASE")]
+pub struct Recipes {
+    #[serde(default = "Vec::new")]
+    pub recipe: Vec<Recipe>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum Type {
     Extract,
     PartialMash,
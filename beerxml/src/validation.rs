@@ -0,0 +1,335 @@
+//! # Validation
+//!
+//! Checks of a recipe for data that is physically impossible, inconsistent,
+//! or missing for brewing calculations.
+//!
+//! Fields are referred to by their path from the recipe, e.g. `hops.hop[0].alpha`.
+use crate::hop::Use;
+use crate::recipe::{Recipe, Type};
+use brew_calculator::units::*;
+use std::fmt;
+
+/// Volume of one kilogram of grain in the mash tun, in liters
+const GRAIN_VOLUME: Liters = 0.67;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Questionable data that calculations can still use
+    Warning,
+    /// Impossible data that makes calculations meaningless
+    Error,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Issue {
+    pub severity: Severity,
+    /// Path to the field, e.g. `fermentables.fermentable[1].yield_`
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        write!(f, "{}: {}: {}", severity, self.path, self.message)
+    }
+}
+
+/// Collects issues while checking a recipe
+#[derive(Default)]
+struct Issues(Vec<Issue>);
+
+impl Issues {
+    fn push(&mut self, severity: Severity, path: String, message: String) {
+        self.0.push(Issue {
+            severity,
+            path,
+            message,
+        });
+    }
+
+    fn error(&mut self, path: String, message: String) {
+        self.push(Severity::Error, path, message);
+    }
+
+    fn warning(&mut self, path: String, message: String) {
+        self.push(Severity::Warning, path, message);
+    }
+
+    /// Error if `value` is negative
+    fn non_negative(&mut self, path: String, value: f32) {
+        if value < 0.0 {
+            self.error(path, format!("{} is negative", value));
+        }
+    }
+
+    /// Error if `value` is negative or larger than 100 %
+    fn percent(&mut self, path: String, value: Option<Percent>) {
+        if let Some(value) = value {
+            if !(0.0..=100.0).contains(&value) {
+                self.error(path, format!("{} % is outside 0 to 100 %", value));
+            }
+        }
+    }
+}
+
+impl Recipe {
+    /// Errors and warnings for the recipe, errors first
+    ///
+    /// An empty list means the recipe is ready for brewing calculations.
+    pub fn validate(&self) -> Vec<Issue> {
+        let mut issues = Issues::default();
+        self.validate_recipe(&mut issues);
+        self.validate_hops(&mut issues);
+        self.validate_fermentables(&mut issues);
+        self.validate_yeasts(&mut issues);
+        self.validate_miscs(&mut issues);
+        self.validate_mash(&mut issues);
+        let mut issues = issues.0;
+        issues.sort_by_key(|issue| std::cmp::Reverse(issue.severity));
+        issues
+    }
+
+    fn validate_recipe(&self, issues: &mut Issues) {
+        if self.batch_size <= 0.0 {
+            issues.error("batch_size".into(), "Batch size must be positive".into());
+        }
+        if self.boil_size <= 0.0 {
+            issues.error("boil_size".into(), "Boil size must be positive".into());
+        } else if self.boil_size < self.batch_size {
+            issues.warning(
+                "boil_size".into(),
+                format!(
+                    "Boil size {} l is smaller than the batch size {} l",
+                    self.boil_size, self.batch_size
+                ),
+            );
+        }
+        issues.non_negative("boil_time".into(), self.boil_time);
+        if self.type_ != Type::Extract {
+            issues.percent("efficiency".into(), Some(self.efficiency));
+            if self.efficiency == 0.0 {
+                issues.error(
+                    "efficiency".into(),
+                    "Efficiency of 0 % for a mashed recipe".into(),
+                );
+            }
+        }
+        if let (Some(og), Some(fg)) = (self.og, self.fg) {
+            if fg > og {
+                issues.error(
+                    "fg".into(),
+                    format!(
+                        "Final gravity {} is higher than the original gravity {}",
+                        fg, og
+                    ),
+                );
+            }
+        }
+        for (path, gravity) in [("og", self.og), ("fg", self.fg)] {
+            if let Some(gravity) = gravity {
+                if !(0.98..=1.2).contains(&gravity) {
+                    issues.error(
+                        path.into(),
+                        format!("Specific gravity {} is outside 0.980 to 1.200", gravity),
+                    );
+                }
+            }
+        }
+        if self.fermentables.fermentable.is_empty() {
+            issues.warning(
+                "fermentables".into(),
+                "No fermentables, gravity and color can not be estimated".into(),
+            );
+        }
+        if self.yeasts.yeast.is_empty() {
+            issues.warning(
+                "yeasts".into(),
+                "No yeast, final gravity can not be estimated".into(),
+            );
+        }
+    }
+
+    fn validate_hops(&self, issues: &mut Issues) {
+        for (idx, hop) in self.hops.hop.iter().enumerate() {
+            let path = |field: &str| format!("hops.hop[{}].{}", idx, field);
+            issues.non_negative(path("amount"), hop.amount);
+            issues.non_negative(path("time"), hop.time);
+            if hop.alpha <= 0.0 || hop.alpha > 100.0 {
+                issues.error(
+                    path("alpha"),
+                    format!("Alpha acids of {} % is outside 0 to 100 %", hop.alpha),
+                );
+            }
+            issues.percent(path("beta"), hop.beta);
+            issues.percent(path("hsi"), hop.hsi);
+            if hop.use_ == Use::Boil && hop.time > self.boil_time {
+                issues.warning(
+                    path("time"),
+                    format!(
+                        "Boil time of {} min is longer than the recipe's boil of {} min",
+                        hop.time, self.boil_time
+                    ),
+                );
+            }
+            if hop.use_ == Use::Mash && self.type_ == Type::Extract {
+                issues.warning(
+                    path("use_"),
+                    "Mash hop in an extract recipe without a mash".into(),
+                );
+            }
+            if hop.form.is_none() {
+                issues.warning(path("form"), "Missing form, leaf is assumed".into());
+            }
+        }
+    }
+
+    fn validate_fermentables(&self, issues: &mut Issues) {
+        let total: Kilograms = self
+            .fermentables
+            .fermentable
+            .iter()
+            .map(|fermentable| fermentable.amount)
+            .sum();
+        for (idx, fermentable) in self.fermentables.fermentable.iter().enumerate() {
+            let path = |field: &str| format!("fermentables.fermentable[{}].{}", idx, field);
+            issues.non_negative(path("amount"), fermentable.amount);
+            issues.percent(path("yield_"), Some(fermentable.yield_));
+            issues.non_negative(path("color"), fermentable.color);
+            issues.percent(path("moisture"), fermentable.moisture);
+            issues.percent(path("coarse_fine_diff"), fermentable.coarse_fine_diff);
+            issues.percent(path("protein"), fermentable.protein);
+            issues.percent(path("max_in_batch"), fermentable.max_in_batch);
+            if let Some(max_in_batch) = fermentable.max_in_batch {
+                let share = 100.0 * fermentable.amount / total;
+                if total > 0.0 && share > max_in_batch {
+                    issues.warning(
+                        path("amount"),
+                        format!(
+                            "{:.1} % of the grist exceeds the maximum of {} %",
+                            share, max_in_batch
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    fn validate_yeasts(&self, issues: &mut Issues) {
+        for (idx, yeast) in self.yeasts.yeast.iter().enumerate() {
+            let path = |field: &str| format!("yeasts.yeast[{}].{}", idx, field);
+            issues.non_negative(path("amount"), yeast.amount);
+            issues.percent(path("attenuation"), yeast.attenuation);
+            if yeast.attenuation.is_none() {
+                issues.warning(
+                    path("attenuation"),
+                    "Missing attenuation, final gravity is estimated from 75 %".into(),
+                );
+            }
+            if let (Some(min), Some(max)) = (yeast.min_temperature, yeast.max_temperature) {
+                if min > max {
+                    issues.error(
+                        path("min_temperature"),
+                        format!(
+                            "Minimum temperature {} °C is above the maximum {} °C",
+                            min, max
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    fn validate_miscs(&self, issues: &mut Issues) {
+        for (idx, misc) in self.miscs.misc.iter().enumerate() {
+            let path = |field: &str| format!("miscs.misc[{}].{}", idx, field);
+            issues.non_negative(path("amount"), misc.amount);
+            issues.non_negative(path("time"), misc.time);
+        }
+    }
+
+    fn validate_mash(&self, issues: &mut Issues) {
+        let steps = &self.mash.mash_steps.mash_step;
+        if self.type_ == Type::Extract {
+            if !steps.is_empty() {
+                issues.warning(
+                    "mash.mash_steps".into(),
+                    "Mash steps in an extract recipe".into(),
+                );
+            }
+            return;
+        }
+        let grain: Kilograms = self
+            .fermentables
+            .fermentable
+            .iter()
+            .filter(|fermentable| fermentable.type_.mashed())
+            .map(|fermentable| fermentable.amount)
+            .sum();
+        let tun_volume = self
+            .equipment
+            .as_ref()
+            .and_then(|equipment| equipment.tun_volume);
+        let mut water: Liters = 0.0;
+        for (idx, step) in steps.iter().enumerate() {
+            let path = |field: &str| format!("mash.mash_steps.mash_step[{}].{}", idx, field);
+            issues.non_negative(path("step_time"), step.step_time);
+            if !(0.0..=100.0).contains(&step.step_temp) {
+                issues.error(
+                    path("step_temp"),
+                    format!(
+                        "Step temperature {} °C is outside 0 to 100 °C",
+                        step.step_temp
+                    ),
+                );
+            }
+            if let Some(infuse_amount) = step.infuse_amount {
+                issues.non_negative(path("infuse_amount"), infuse_amount);
+                water += infuse_amount;
+                if let Some(tun_volume) = tun_volume {
+                    let mash_volume = water + grain * GRAIN_VOLUME;
+                    if mash_volume > tun_volume {
+                        issues.warning(
+                            path("infuse_amount"),
+                            format!(
+                                "Mash volume of {:.1} l exceeds the tun volume of {} l",
+                                mash_volume, tun_volume
+                            ),
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn issue_display() {
+        let issue = Issue {
+            severity: Severity::Error,
+            path: "hops.hop[0].alpha".into(),
+            message: "Alpha acids of 0 % is outside 0 to 100 %".into(),
+        };
+        assert_eq!(
+            issue.to_string(),
+            "error: hops.hop[0].alpha: Alpha acids of 0 % is outside 0 to 100 %"
+        );
+        assert!(Severity::Error > Severity::Warning);
+    }
+
+    #[test]
+    fn percent_range() {
+        let mut issues = Issues::default();
+        issues.percent("yield_".into(), Some(101.0));
+        issues.percent("moisture".into(), None);
+        issues.non_negative("amount".into(), -1.0);
+        assert_eq!(issues.0.len(), 2);
+    }
+}
@@ -6,34 +6,67 @@ use brew_calculator::units::*;
 use serde::Deserialize;
 use serde::Deserializer;
 
-#[derive(Deserialize, Debug, PartialEq)]
+#[derive(Deserialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "UPPERCASE")]
 pub struct Yeast {
-    name: String,
+    pub name: String,
     version: u8,
     #[serde(rename = "TYPE")]
-    type_: Type,
-    form: Form,
+    pub type_: Type,
+    pub form: Form,
     ///The amount of yeast, measured in liters. For a starter this is the size of the starter. If
     ///the flag [`amount_is_weight`](struct.Yeast.html) is set to `true` then this measurement is in kilograms and not
     ///liters.
-    amount: f32,
+    pub amount: f32,
     #[serde(default)]
     #[serde(deserialize_with = "utils::opt_bool_de_from_str")]
-    amount_is_weight: Option<bool>,
-    laboratory: Option<String>,
-    product_id: Option<String>,
-    min_temperature: Option<Celsius>,
-    max_temperature: Option<Celsius>,
-    flocculation: Option<Flocculation>,
-    attenuation: Option<Percent>,
+    pub amount_is_weight: Option<bool>,
+    pub laboratory: Option<String>,
+    pub product_id: Option<String>,
+    ///The minimum recommended temperature for fermenting this yeast strain in degrees Celsius.
+    pub min_temperature: Option<Celsius>,
+    ///The maximum recommended temperature for fermenting this yeast strain in degrees Celsius.
+    pub max_temperature: Option<Celsius>,
+    pub flocculation: Option<Flocculation>,
+    ///Average attenuation for this yeast strain.
+    pub attenuation: Option<Percent>,
     notes: Option<String>,
-    best_for: Option<String>,
-    times_cultured: Option<u8>,
+    ///Styles or types of beer this yeast strain is best suited for.
+    pub best_for: Option<String>,
+    ///Number of times this yeast has been reused as a harvested culture.
+    pub times_cultured: Option<u8>,
     max_reuse: Option<u8>,
     #[serde(default)]
     #[serde(deserialize_with = "utils::opt_bool_de_from_str")]
     add_to_secondary: Option<bool>,
+    ///Amount in inventory as a display string with units, e.g. "2.5 lb".
+    pub inventory: Option<String>,
+}
+
+impl Yeast {
+    ///Yeast with only the required fields set.
+    pub fn new(name: String, type_: Type, form: Form, amount: f32) -> Self {
+        Self {
+            name,
+            version: 1,
+            type_,
+            form,
+            amount,
+            amount_is_weight: None,
+            laboratory: None,
+            product_id: None,
+            min_temperature: None,
+            max_temperature: None,
+            flocculation: None,
+            attenuation: None,
+            notes: None,
+            best_for: None,
+            times_cultured: None,
+            max_reuse: None,
+            add_to_secondary: None,
+            inventory: None,
+        }
+    }
 }
 
 #[derive(Deserialize, Debug, PartialEq, Default)]
@@ -43,8 +76,8 @@ pub struct Yeasts {
     pub yeast: Vec<Yeast>,
 }
 
-#[derive(Deserialize, Debug, PartialEq)]
-enum Type {
+#[derive(Deserialize, Copy, Clone, Debug, PartialEq)]
+pub enum Type {
     Ale,
     Lager,
     Wheat,
@@ -52,16 +85,16 @@ enum Type {
     Champagne,
 }
 
-#[derive(Deserialize, Debug, PartialEq)]
-enum Form {
+#[derive(Deserialize, Copy, Clone, Debug, PartialEq)]
+pub enum Form {
     Liquid,
     Dry,
     Slant,
     Culture,
 }
 
-#[derive(Debug, PartialEq)]
-enum Flocculation {
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Flocculation {
     Low,
     Medium,
     High,
@@ -119,6 +152,7 @@ mod beerxml {
             times_cultured: None,
             max_reuse: None,
             add_to_secondary: None,
+            inventory: None,
         };
         assert_eq!(parsed_yeast, true_yeast);
     }
@@ -167,6 +201,7 @@ mod beerxml {
             times_cultured: None,
             max_reuse: None,
             add_to_secondary: None,
+            inventory: None,
         };
         assert_eq!(parsed_yeast, true_yeast);
     }
@@ -0,0 +1,590 @@
+//! # BeerJSON
+//!
+//! Recipes in the [BeerJSON](https://github.com/beerjson/beerjson) format.
+//!
+//! Only the fields needed for a `bryggio::Recipe` are parsed.
+//! BeerJSON quantities carry their own unit,
+//! and are converted to the units used by BeerXML.
+use crate::bryggio::{
+    process,
+    quantity::{Color, Gravity, Mass, Temperature, Volume},
+    recipe::{RecipeSrc, DEFAULT_EFFICIENCY},
+    system::System,
+    Recipe,
+};
+use brew_calculator::units::*;
+use serde::Deserialize;
+use std::convert::From;
+
+/// Top level BeerJSON document
+#[derive(Deserialize, Debug)]
+pub struct BeerJson {
+    pub beerjson: BeerJsonContents,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct BeerJsonContents {
+    pub version: Option<f32>,
+    #[serde(default)]
+    pub recipes: Vec<BeerJsonRecipe>,
+    #[serde(default)]
+    pub equipments: Vec<BeerJsonEquipment>,
+}
+
+impl BeerJsonContents {
+    /// Recipes of the document, brewed on its first equipment
+    ///
+    /// BeerJSON recipes don't refer to an equipment, so the recipes of a document with
+    /// several equipments are brewed on the first.
+    pub fn into_recipes(self) -> Vec<Recipe<BeerJsonSrc>> {
+        let system = self.equipments.into_iter().next().map(system);
+        self.recipes
+            .into_iter()
+            .map(|recipe| {
+                let mut recipe = Recipe::from(recipe);
+                if let Some(system) = system {
+                    recipe.system = system;
+                }
+                recipe
+            })
+            .collect()
+    }
+}
+
+/// Value with a unit, like `{"unit": "kg", "value": 4.5}`
+#[derive(Deserialize, Debug, Clone)]
+pub struct Measure {
+    pub unit: String,
+    pub value: f32,
+}
+
+impl Measure {
+    fn mass(&self) -> Option<Mass> {
+        match self.unit.as_str() {
+            "mg" => Some(Mass::from_grams(self.value / 1000.0)),
+            "g" => Some(Mass::from_grams(self.value)),
+            "kg" => Some(Mass::from_kilograms(self.value)),
+            "lb" => Some(Mass::from_pounds(self.value)),
+            "oz" => Some(Mass::from_ounces(self.value)),
+            _ => None,
+        }
+    }
+
+    fn volume(&self) -> Option<Volume> {
+        match self.unit.as_str() {
+            "ml" => Some(Volume::from_liters(self.value / 1000.0)),
+            "l" => Some(Volume::from_liters(self.value)),
+            "gal" => Some(Volume::from_gallons(self.value)),
+            "qt" => Some(Volume::from_gallons(self.value / 4.0)),
+            "floz" => Some(Volume::from_gallons(self.value / 128.0)),
+            _ => None,
+        }
+    }
+
+    fn minutes(&self) -> Minutes {
+        match self.unit.as_str() {
+            "sec" => self.value / 60.0,
+            "hr" => self.value * 60.0,
+            "day" => self.value * 24.0 * 60.0,
+            "week" => self.value * 7.0 * 24.0 * 60.0,
+            _ => self.value,
+        }
+    }
+
+    fn temperature(&self) -> Temperature {
+        match self.unit.as_str() {
+            "F" => Temperature::from_fahrenheit(self.value),
+            _ => Temperature::from_celsius(self.value),
+        }
+    }
+
+    fn gravity(&self) -> Gravity {
+        match self.unit.as_str() {
+            "plato" | "brix" => Gravity::from_plato(self.value),
+            _ => Gravity::from_specific_gravity(self.value),
+        }
+    }
+
+    fn color(&self) -> Color {
+        match self.unit.as_str() {
+            "EBC" => Color::from_ebc(self.value),
+            "Lovi" => Color::from_lovibond(self.value),
+            _ => Color::from_srm(self.value),
+        }
+    }
+
+    /// Volume per mass in l/kg, like `{"unit": "qt/lb", "value": 0.5}`
+    fn specific_volume(&self) -> Option<f32> {
+        let (volume, mass) = self.unit.split_once('/')?;
+        let volume = Measure {
+            unit: volume.into(),
+            value: self.value,
+        }
+        .volume()?;
+        let mass = Measure {
+            unit: mass.into(),
+            value: 1.0,
+        }
+        .mass()?;
+        Some(volume.liters() / mass.kilograms())
+    }
+
+    /// Mass in kg or volume in l, and whether it is a mass
+    fn amount(&self) -> (f32, bool) {
+        if let Some(mass) = self.mass() {
+            (mass.kilograms(), true)
+        } else if let Some(volume) = self.volume() {
+            (volume.liters(), false)
+        } else {
+            (self.value, false)
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct BeerJsonRecipe {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_: Option<String>,
+    pub author: Option<String>,
+    pub coauthor: Option<String>,
+    pub created: Option<String>,
+    pub batch_size: Measure,
+    pub efficiency: Option<Efficiency>,
+    pub style: Option<BeerJsonStyle>,
+    #[serde(default)]
+    pub ingredients: Ingredients,
+    pub mash: Option<MashProcedure>,
+    pub boil: Option<BoilProcedure>,
+    pub original_gravity: Option<Measure>,
+    pub final_gravity: Option<Measure>,
+    pub notes: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Efficiency {
+    pub brewhouse: Measure,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct BeerJsonStyle {
+    pub name: Option<String>,
+    pub category: Option<String>,
+    pub category_number: Option<u32>,
+    pub style_letter: Option<String>,
+    pub style_guide: Option<String>,
+    #[serde(rename = "type")]
+    pub type_: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct Ingredients {
+    #[serde(default)]
+    pub fermentable_additions: Vec<FermentableAddition>,
+    #[serde(default)]
+    pub hop_additions: Vec<HopAddition>,
+    #[serde(default)]
+    pub culture_additions: Vec<CultureAddition>,
+    #[serde(default)]
+    pub miscellaneous_additions: Vec<MiscAddition>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct BeerJsonEquipment {
+    pub name: Option<String>,
+    #[serde(default)]
+    pub equipment_items: Vec<EquipmentItem>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct EquipmentItem {
+    pub name: Option<String>,
+    /// "HLT", "Mash Tun", "Lauter Tun", "Brew Kettle", "Fermenter", "Aging Vessel"
+    /// or "Packaging Vessel"
+    pub form: String,
+    pub maximum_volume: Option<Measure>,
+    /// Volume left in the vessel
+    pub loss: Option<Measure>,
+    pub grain_absorption_rate: Option<Measure>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct MashProcedure {
+    pub name: Option<String>,
+    pub grain_temperature: Option<Measure>,
+    #[serde(default)]
+    pub mash_steps: Vec<BeerJsonMashStep>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct BeerJsonMashStep {
+    pub name: Option<String>,
+    /// "infusion", "temperature", "decoction", "souring mash", "souring wort",
+    /// "drain mash tun" or "sparge"
+    #[serde(rename = "type")]
+    pub type_: Option<String>,
+    /// Infused water
+    pub amount: Option<Measure>,
+    pub step_temperature: Measure,
+    pub step_time: Option<Measure>,
+    pub ramp_time: Option<Measure>,
+    pub end_temperature: Option<Measure>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct BoilProcedure {
+    pub pre_boil_size: Option<Measure>,
+    pub boil_time: Option<Measure>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Timing {
+    #[serde(rename = "use")]
+    pub use_: Option<String>,
+    pub time: Option<Measure>,
+    pub duration: Option<Measure>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct FermentableAddition {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_: Option<String>,
+    #[serde(rename = "yield")]
+    pub yield_: Option<Yield>,
+    pub color: Option<Measure>,
+    pub timing: Option<Timing>,
+    pub amount: Measure,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Yield {
+    pub fine_grind: Option<Measure>,
+    pub potential: Option<Measure>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct HopAddition {
+    pub name: String,
+    pub alpha_acid: Measure,
+    pub form: Option<String>,
+    pub timing: Option<Timing>,
+    pub amount: Measure,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CultureAddition {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_: Option<String>,
+    pub form: Option<String>,
+    pub product_id: Option<String>,
+    pub amount: Option<Measure>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct MiscAddition {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_: Option<String>,
+    pub timing: Option<Timing>,
+    pub amount: Measure,
+}
+
+impl From<BeerJsonRecipe> for Recipe<BeerJsonSrc> {
+    fn from(bj: BeerJsonRecipe) -> Self {
+        let batch_size = bj.batch_size.volume().unwrap_or_default().liters();
+        let (pre_boil_size, boil_time) = match bj.boil {
+            Some(boil) => (
+                boil.pre_boil_size.and_then(|size| size.volume()),
+                boil.boil_time.map(|time| time.minutes()),
+            ),
+            None => (None, None),
+        };
+        Recipe {
+            name: bj.name,
+            type_: match bj.type_.as_deref() {
+                Some("extract") => beerxml::Type::Extract,
+                Some("partial mash") => beerxml::Type::PartialMash,
+                _ => beerxml::Type::AllGrain,
+            },
+            style: style(bj.style),
+            brewer: bj.author.unwrap_or_default(),
+            asst_brewer: bj.coauthor,
+            equipment: None,
+            batch_size,
+            pre_boil_gravity: None,
+            og: bj
+                .original_gravity
+                .map(|og| og.gravity().specific_gravity()),
+            fg: bj.final_gravity.map(|fg| fg.gravity().specific_gravity()),
+            efficiency: bj
+                .efficiency
+                .map_or(DEFAULT_EFFICIENCY, |eff| eff.brewhouse.value),
+            hops: bj.ingredients.hop_additions.into_iter().map(hop).collect(),
+            fermentables: bj
+                .ingredients
+                .fermentable_additions
+                .into_iter()
+                .map(fermentable)
+                .collect(),
+            yeasts: bj
+                .ingredients
+                .culture_additions
+                .into_iter()
+                .map(yeast)
+                .collect(),
+            waters: Vec::new(),
+            miscs: bj
+                .ingredients
+                .miscellaneous_additions
+                .into_iter()
+                .map(misc)
+                .collect(),
+            mash: bj
+                .mash
+                .map_or_else(|| process::Mash::from_steps(Vec::new()), mash),
+            boil: process::Boil::from_beerxml_recipe(
+                pre_boil_size.map_or(batch_size, Volume::liters),
+                boil_time.unwrap_or(60.0),
+            ),
+            fermentation: process::Fermentation {},
+            carbonation: process::Carbonation {},
+            notes: bj.notes,
+            taste_notes: None,
+            taste_rating: None,
+            date: bj.created,
+            ibu_method: Default::default(),
+            hop_storage: None,
+            system: System::traditional(),
+            recipe_src: BeerJsonSrc {},
+        }
+    }
+}
+
+/// Original values in the BeerJSON source recipe
+#[derive(Debug, Clone, Copy)]
+pub struct BeerJsonSrc {}
+
+impl RecipeSrc for BeerJsonSrc {}
+
+/// BeerJSON styles have no ranges in the recipe,
+/// so the ranges are left empty.
+fn style(bj: Option<BeerJsonStyle>) -> beerxml::Style {
+    use beerxml::style::Type;
+    let bj = bj.unwrap_or(BeerJsonStyle {
+        name: None,
+        category: None,
+        category_number: None,
+        style_letter: None,
+        style_guide: None,
+        type_: None,
+    });
+    beerxml::Style {
+        name: bj.name.unwrap_or_default(),
+        category: bj.category.unwrap_or_default(),
+        version: 1,
+        category_number: bj
+            .category_number
+            .map(|number| number.to_string())
+            .unwrap_or_default(),
+        style_letter: bj.style_letter.unwrap_or_default(),
+        style_guide: bj.style_guide.unwrap_or_default(),
+        type_: match bj.type_.as_deref() {
+            Some("lager") => Type::Lager,
+            Some("mead") => Type::Mead,
+            Some("cider") => Type::Cider,
+            Some("wine") | Some("kombucha") | Some("soda") | Some("other") => Type::Mixed,
+            _ => Type::Ale,
+        },
+        og_min: 1.0,
+        og_max: 1.0,
+        fg_min: 1.0,
+        fg_max: 1.0,
+        ibu_min: 0.0,
+        ibu_max: 0.0,
+        color_min: 0.0,
+        color_max: 0.0,
+        carb_min: None,
+        carb_max: None,
+        abv_min: None,
+        abv_max: None,
+        notes: None,
+        profile: None,
+        ingredients: None,
+        examples: None,
+    }
+}
+
+/// Traditional system with the volumes of the mash and lauter tuns,
+/// or brew-in-a-bag when the grain is mashed in the brew kettle
+fn system(bj: BeerJsonEquipment) -> System {
+    let item = |form: &str| bj.equipment_items.iter().find(|item| item.form == form);
+    let mash_tun = item("Mash Tun");
+    let lauter_tun = item("Lauter Tun").or(mash_tun);
+    let (vessel, base) = match (mash_tun.or(lauter_tun), item("Brew Kettle")) {
+        (Some(tun), _) => (tun, System::traditional()),
+        (None, Some(kettle)) if kettle.grain_absorption_rate.is_some() => {
+            (kettle, System::brew_in_a_bag())
+        }
+        _ => return System::traditional(),
+    };
+    let liters = |measure: &Option<Measure>| {
+        measure
+            .as_ref()
+            .and_then(Measure::volume)
+            .map(Volume::liters)
+    };
+    System {
+        grain_absorption: vessel
+            .grain_absorption_rate
+            .as_ref()
+            .and_then(Measure::specific_volume)
+            .unwrap_or(base.grain_absorption),
+        lauter_deadspace: lauter_tun
+            .and_then(|tun| liters(&tun.loss))
+            .unwrap_or(base.lauter_deadspace),
+        mash_volume: liters(&vessel.maximum_volume),
+        ..base
+    }
+}
+
+/// Draining and sparging are left out, they are not rests of the mash
+fn mash(bj: MashProcedure) -> process::Mash {
+    let steps = bj
+        .mash_steps
+        .into_iter()
+        .filter(|step| !matches!(step.type_.as_deref(), Some("drain mash tun" | "sparge")))
+        .map(mash_step)
+        .collect();
+    let mut mash = process::Mash::from_steps(steps);
+    if let Some(temp) = bj.grain_temperature {
+        mash.grain_temp = temp.temperature().celsius();
+    }
+    mash
+}
+
+fn mash_step(bj: BeerJsonMashStep) -> beerxml::mash::MashStep {
+    use beerxml::mash::Type;
+    beerxml::mash::MashStep {
+        name: bj.name.unwrap_or_default(),
+        version: 1,
+        type_: match bj.type_.as_deref() {
+            Some("infusion") => Type::Infusion,
+            Some("decoction") => Type::Decoction,
+            _ => Type::Temperature,
+        },
+        infuse_amount: bj
+            .amount
+            .and_then(|amount| amount.volume())
+            .map(Volume::liters),
+        step_temp: bj.step_temperature.temperature().celsius(),
+        step_time: bj.step_time.map_or(0.0, |time| time.minutes()),
+        ramp_time: bj.ramp_time.map(|time| time.minutes()),
+        end_temp: bj.end_temperature.map(|temp| temp.temperature().celsius()),
+    }
+}
+
+fn fermentable(bj: FermentableAddition) -> beerxml::Fermentable {
+    use beerxml::fermentable::Type;
+    let type_ = match bj.type_.as_deref() {
+        Some("sugar") => Type::Sugar,
+        Some("extract") => Type::Extract,
+        Some("dry extract") => Type::DryExtract,
+        Some("grain") => Type::Grain,
+        _ => Type::Adjunct,
+    };
+    let yield_ = bj
+        .yield_
+        .and_then(|yield_| match (yield_.fine_grind, yield_.potential) {
+            (Some(fine_grind), _) => Some(fine_grind.value),
+            (None, Some(potential)) => Some(
+                100.0 * potential.gravity().points()
+                    / Gravity::from_specific_gravity(1.046).points(),
+            ),
+            (None, None) => None,
+        });
+    let color = bj.color.map_or(0.0, |color| color.color().lovibond());
+    let (amount, _) = bj.amount.amount();
+    let mut fermentable =
+        beerxml::Fermentable::new(bj.name, type_, amount, yield_.unwrap_or(0.0), color);
+    fermentable.add_after_boil = bj.timing.and_then(|timing| match timing.use_.as_deref() {
+        Some("add_to_fermentation") | Some("add_to_package") => Some(true),
+        Some(_) => Some(false),
+        None => None,
+    });
+    fermentable
+}
+
+fn hop(bj: HopAddition) -> beerxml::Hop {
+    use beerxml::hop::{Form, Use};
+    let (use_, time) = match bj.timing {
+        Some(timing) => {
+            let use_ = match timing.use_.as_deref() {
+                Some("add_to_mash") => Use::Mash,
+                Some("add_to_fermentation") | Some("add_to_package") => Use::DryHop,
+                _ => Use::Boil,
+            };
+            let time = timing.duration.or(timing.time).map_or(0.0, |t| t.minutes());
+            (use_, time)
+        }
+        None => (Use::Boil, 0.0),
+    };
+    let (amount, _) = bj.amount.amount();
+    let mut hop = beerxml::Hop::new(bj.name, bj.alpha_acid.value, amount, use_, time);
+    hop.form = match bj.form.as_deref() {
+        Some("pellet") => Some(Form::Pellet),
+        Some("plug") => Some(Form::Plug),
+        Some("leaf") | Some("leaf (wet)") => Some(Form::Leaf),
+        _ => None,
+    };
+    hop
+}
+
+fn yeast(bj: CultureAddition) -> beerxml::Yeast {
+    use beerxml::yeast::{Form, Type};
+    let type_ = match bj.type_.as_deref() {
+        Some("lager") => Type::Lager,
+        Some("wine") => Type::Wine,
+        Some("champagne") => Type::Champagne,
+        _ => Type::Ale,
+    };
+    let form = match bj.form.as_deref() {
+        Some("dry") => Form::Dry,
+        Some("slant") => Form::Slant,
+        Some("culture") => Form::Culture,
+        _ => Form::Liquid,
+    };
+    let (amount, is_weight) = bj.amount.map_or((0.0, false), |amount| amount.amount());
+    let mut yeast = beerxml::Yeast::new(bj.name, type_, form, amount);
+    yeast.amount_is_weight = Some(is_weight);
+    yeast.product_id = bj.product_id;
+    yeast
+}
+
+fn misc(bj: MiscAddition) -> beerxml::Misc {
+    use beerxml::misc::{Type, Use};
+    let type_ = match bj.type_.as_deref() {
+        Some("spice") => Type::Spice,
+        Some("fining") => Type::Fining,
+        Some("water agent") => Type::WaterAgent,
+        Some("herb") => Type::Herb,
+        Some("flavor") => Type::Flavor,
+        _ => Type::Other,
+    };
+    let (use_, time) = match bj.timing {
+        Some(timing) => {
+            let use_ = match timing.use_.as_deref() {
+                Some("add_to_mash") => Use::Mash,
+                Some("add_to_fermentation") => Use::Primary,
+                Some("add_to_package") => Use::Bottling,
+                _ => Use::Boil,
+            };
+            let time = timing.duration.or(timing.time).map_or(0.0, |t| t.minutes());
+            (use_, time)
+        }
+        None => (Use::Boil, 0.0),
+    };
+    let (amount, _) = bj.amount.amount();
+    beerxml::Misc::new(bj.name, type_, use_, time, amount)
+}
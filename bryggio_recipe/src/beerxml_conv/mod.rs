@@ -1,10 +1,14 @@
-use crate::bryggio::{process, recipe::RecipeSrc, Recipe};
+use crate::bryggio::{process, recipe::RecipeSrc, system::System, Recipe};
 use beerxml;
 use brew_calculator::ibu;
 use std::convert::From;
 
 impl From<beerxml::Recipe> for Recipe<BeerXmlSrc> {
     fn from(beerxml_recipe: beerxml::Recipe) -> Self {
+        let system = beerxml_recipe
+            .equipment
+            .as_ref()
+            .map_or_else(System::traditional, System::from_equipment);
         Recipe {
             name: beerxml_recipe.name,
             type_: beerxml_recipe.type_,
@@ -24,7 +28,7 @@ impl From<beerxml::Recipe> for Recipe<BeerXmlSrc> {
             miscs: beerxml_recipe.miscs.misc,
             yeasts: beerxml_recipe.yeasts.yeast,
             waters: beerxml_recipe.waters.water,
-            mash: process::Mash {},
+            mash: process::Mash::from_beerxml_mash(beerxml_recipe.mash),
             boil: process::Boil::from_beerxml_recipe(
                 beerxml_recipe.boil_size,
                 beerxml_recipe.boil_time,
@@ -36,6 +40,8 @@ impl From<beerxml::Recipe> for Recipe<BeerXmlSrc> {
             taste_rating: beerxml_recipe.taste_rating,
             date: beerxml_recipe.date,
             ibu_method: beerxml_recipe.ibu_method.unwrap_or_default(),
+            hop_storage: None,
+            system,
             recipe_src: BeerXmlSrc {
                 ibu_method: beerxml_recipe.ibu_method,
             },
@@ -1,24 +1,154 @@
+use beer_recipe::bryggio::bitterness::{IbuReport, Model};
 use beer_recipe::bryggio::Recipe;
-use std::fs;
+use beer_recipe::load::{self, LoadError, RecipeSource};
+use brew_calculator::ibu;
+use serde::Serialize;
 use std::io::prelude::*;
-use std::path;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::{io, process};
+use structopt::StructOpt;
+use thiserror::Error;
+
+/// Calculate the bitterness of BeerXML, BeerJSON or Brewfather recipes
+#[derive(Debug, StructOpt)]
+#[structopt(name = "hop_calc")]
+struct Opt {
+    /// Recipe files, reads from stdin if none are given or for '-'
+    #[structopt(parse(from_os_str))]
+    recipes: Vec<PathBuf>,
+    /// IBU method, overriding the one in the recipe [Tinseth, Rager, Garetz]
+    #[structopt(long)]
+    ibu_method: Option<IbuMethod>,
+    /// Original gravity, overriding the one in the recipe
+    #[structopt(long)]
+    og: Option<f32>,
+    /// Output format [table, json]
+    #[structopt(long, default_value = "table")]
+    format: Format,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct IbuMethod(ibu::Method);
+
+impl FromStr for IbuMethod {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "tinseth" => Ok(IbuMethod(ibu::Method::Tinseth(ibu::Tinseth {}))),
+            "rager" => Ok(IbuMethod(ibu::Method::Rager)),
+            "garetz" => Ok(IbuMethod(ibu::Method::Garetz)),
+            _ => Err(format!("Unknown IBU method '{}'", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Format {
+    Table,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "table" => Ok(Format::Table),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("Unknown output format '{}'", s)),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+enum HopCalcError {
+    #[error("Could not read '{0}': {1}")]
+    Read(String, io::Error),
+    #[error("Could not load '{0}': {1}")]
+    Load(String, LoadError),
+    #[error("Could not serialize output: {0}")]
+    Serialize(#[from] serde_json::Error),
+}
+
+#[derive(Debug, Serialize)]
+struct Output {
+    source: String,
+    name: String,
+    ibu: f32,
+    bu_gu: f32,
+}
 
 fn main() {
-    let recipe_file = path::Path::new("src/bin/recipes/BellsTwoHeartedAleClone.xml");
-    let recipe = read_file_to_string(recipe_file).unwrap();
-
-    let recipe = serde_xml_rs::from_str::<beerxml::Recipe>(&recipe).unwrap();
-    let recipe = Recipe::from(recipe);
-    println!(
-        "Total IBU for '{}': {}",
-        recipe_file.file_stem().unwrap().to_str().unwrap(),
-        recipe.ibu()
-    );
-}
-
-fn read_file_to_string<P: AsRef<path::Path>>(file_name: P) -> std::io::Result<String> {
-    let mut file = fs::File::open(file_name)?;
-    let mut contents = String::new();
-    file.read_to_string(&mut contents)?;
-    Ok(contents)
+    let opt = Opt::from_args();
+    if let Err(err) = run(opt) {
+        eprintln!("error: {}", err);
+        process::exit(1);
+    }
+}
+
+fn run(opt: Opt) -> Result<(), HopCalcError> {
+    let sources = if opt.recipes.is_empty() {
+        vec![PathBuf::from("-")]
+    } else {
+        opt.recipes.clone()
+    };
+    let outputs = sources
+        .iter()
+        .map(|source| calc(source, &opt))
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+    match opt.format {
+        Format::Table => {
+            println!(
+                "{:<40} {:<30} {:>6} {:>6}",
+                "Source", "Recipe", "IBU", "BU:GU"
+            );
+            for output in outputs {
+                println!(
+                    "{:<40} {:<30} {:>6.1} {:>6.2}",
+                    output.source, output.name, output.ibu, output.bu_gu
+                );
+            }
+        }
+        Format::Json => println!("{}", serde_json::to_string_pretty(&outputs)?),
+    }
+    Ok(())
+}
+
+fn calc(source: &Path, opt: &Opt) -> Result<Vec<Output>, HopCalcError> {
+    let source_name = source.display().to_string();
+    let recipes = if source == Path::new("-") {
+        let mut contents = Vec::new();
+        io::stdin()
+            .read_to_end(&mut contents)
+            .map_err(|err| HopCalcError::Read(source_name.clone(), err))?;
+        load::from_bytes(&contents)
+    } else {
+        load::load(source)
+    }
+    .map_err(|err| HopCalcError::Load(source_name.clone(), err))?;
+    Ok(recipes
+        .into_iter()
+        .map(|mut recipe| {
+            if let Some(og) = opt.og {
+                recipe.set_og(og);
+            }
+            let report = report(&recipe, opt.ibu_method);
+            Output {
+                source: source_name.clone(),
+                name: recipe.name,
+                ibu: report.ibu,
+                bu_gu: report.bu_gu,
+            }
+        })
+        .collect())
+}
+
+fn report(recipe: &Recipe<RecipeSource>, method: Option<IbuMethod>) -> IbuReport {
+    match method {
+        Some(IbuMethod(method)) => recipe.ibu_report(&Model::Boil(method)),
+        None => recipe.ibu_report(&Model::Boil(recipe.ibu_method())),
+    }
 }
@@ -0,0 +1,128 @@
+use beer_recipe::bryggio::analytics::{Distribution, RecipeStats, Usage};
+use beer_recipe::load::{self, Failure};
+use serde::Serialize;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::{io, process};
+use structopt::StructOpt;
+use thiserror::Error;
+
+/// Statistics over a directory tree of recipes
+#[derive(Debug, StructOpt)]
+#[structopt(name = "recipe_stats")]
+struct Opt {
+    /// Recipe archive directory, searched recursively for '.xml' and '.json' files
+    #[structopt(parse(from_os_str))]
+    recipe_dir: PathBuf,
+    /// Output format [csv, json]
+    #[structopt(long, default_value = "json")]
+    format: Format,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Format {
+    Csv,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "csv" => Ok(Format::Csv),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("Unknown output format '{}'", s)),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+enum RecipeStatsError {
+    #[error("Could not list '{0}': {1}")]
+    ListDir(String, io::Error),
+    #[error("Could not serialize output: {0}")]
+    Serialize(#[from] serde_json::Error),
+}
+
+#[derive(Debug, Serialize)]
+struct Output {
+    stats: RecipeStats,
+    failures: Vec<Failure>,
+}
+
+fn main() {
+    let opt = Opt::from_args();
+    if let Err(err) = run(opt) {
+        eprintln!("error: {}", err);
+        process::exit(1);
+    }
+}
+
+fn run(opt: Opt) -> Result<(), RecipeStatsError> {
+    let (recipes, failures) = load::load_dir(&opt.recipe_dir)
+        .map_err(|err| RecipeStatsError::ListDir(opt.recipe_dir.display().to_string(), err))?;
+    let output = Output {
+        stats: RecipeStats::new(&recipes),
+        failures,
+    };
+    match opt.format {
+        Format::Json => println!("{}", serde_json::to_string_pretty(&output)?),
+        Format::Csv => {
+            print_csv(&output.stats);
+            for failure in output.failures {
+                eprintln!("warning: skipped '{}': {}", failure.path, failure.error);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn print_csv(stats: &RecipeStats) {
+    println!("metric,key,count,min,max,mean,median");
+    let print_dist = |metric: &str, key: &str, dist: &Distribution| {
+        println!(
+            "{},{},{},{},{},{},{}",
+            metric,
+            csv_field(key),
+            dist.count,
+            dist.min,
+            dist.max,
+            dist.mean,
+            dist.median
+        )
+    };
+    let print_usage = |metric: &str, usage: &[Usage]| {
+        for ingredient in usage {
+            println!(
+                "{},{},{},,,,",
+                metric,
+                csv_field(&ingredient.name),
+                ingredient.recipes
+            );
+        }
+    };
+    println!("recipes,,{},,,,", stats.recipes);
+    for (metric, dist) in &[("ibu", stats.ibu), ("og", stats.og), ("color", stats.color)] {
+        if let Some(dist) = dist {
+            print_dist(metric, "", dist);
+        }
+    }
+    for (use_, dist) in &stats.hop_rates {
+        print_dist("hop_rate", use_, dist);
+    }
+    for (name, dist) in &stats.grain_bill {
+        print_dist("grain_bill", name, dist);
+    }
+    print_usage("yeast", &stats.yeasts);
+    print_usage("hop", &stats.hops);
+    print_usage("fermentable", &stats.fermentables);
+    print_usage("misc", &stats.miscs);
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains(&[',', '"', '\n'][..]) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
@@ -0,0 +1,422 @@
+//! # Brewfather
+//!
+//! Recipes in the JSON format exported by [Brewfather](https://brewfather.app).
+//!
+//! Only the fields needed for a `bryggio::Recipe` are parsed.
+//! Amounts are given in kg for fermentables and in g for hops,
+//! and dry hop times are given in days.
+use crate::bryggio::{
+    process,
+    recipe::{RecipeSrc, DEFAULT_EFFICIENCY},
+    system::System,
+    Recipe,
+};
+use brew_calculator::ibu;
+use brew_calculator::units::*;
+use serde::Deserialize;
+use serde_json::Value;
+use std::convert::From;
+
+const MINUTES_PER_DAY: Minutes = 24.0 * 60.0;
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BrewfatherRecipe {
+    #[serde(rename = "_id")]
+    pub id: Option<String>,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_: Option<String>,
+    pub author: Option<String>,
+    pub batch_size: Liters,
+    pub boil_size: Option<Liters>,
+    pub boil_time: Option<Minutes>,
+    pub efficiency: Option<Percent>,
+    pub og: Option<SpecificGravity>,
+    pub fg: Option<SpecificGravity>,
+    pub ibu_formula: Option<String>,
+    pub style: Option<BrewfatherStyle>,
+    #[serde(default)]
+    pub fermentables: Vec<BrewfatherFermentable>,
+    #[serde(default)]
+    pub hops: Vec<BrewfatherHop>,
+    #[serde(default)]
+    pub yeasts: Vec<BrewfatherYeast>,
+    #[serde(default)]
+    pub miscs: Vec<BrewfatherMisc>,
+    pub mash: Option<BrewfatherMash>,
+    pub equipment: Option<BrewfatherEquipment>,
+    pub notes: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BrewfatherStyle {
+    pub name: Option<String>,
+    pub category: Option<String>,
+    pub category_number: Option<Value>,
+    pub style_letter: Option<String>,
+    pub style_guide: Option<String>,
+    #[serde(rename = "type")]
+    pub type_: Option<String>,
+    pub og_min: Option<SpecificGravity>,
+    pub og_max: Option<SpecificGravity>,
+    pub fg_min: Option<SpecificGravity>,
+    pub fg_max: Option<SpecificGravity>,
+    pub ibu_min: Option<Ibu>,
+    pub ibu_max: Option<Ibu>,
+    pub color_min: Option<SRMColor>,
+    pub color_max: Option<SRMColor>,
+    pub carb_min: Option<VolumesCO2>,
+    pub carb_max: Option<VolumesCO2>,
+    pub abv_min: Option<Abv>,
+    pub abv_max: Option<Abv>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BrewfatherFermentable {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_: Option<String>,
+    /// kg
+    pub amount: Kilograms,
+    /// °L
+    pub color: Option<f32>,
+    pub potential_percentage: Option<Percent>,
+    pub add_after_boil: Option<Value>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BrewfatherHop {
+    pub name: String,
+    pub alpha: Percent,
+    /// g
+    pub amount: f32,
+    /// Minutes, or days for dry hops
+    pub time: Option<f32>,
+    #[serde(rename = "use")]
+    pub use_: Option<String>,
+    /// Hop form
+    #[serde(rename = "type")]
+    pub type_: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BrewfatherYeast {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_: Option<String>,
+    pub form: Option<String>,
+    pub amount: Option<f32>,
+    /// "g", "ml" or "pkg"
+    pub unit: Option<String>,
+    pub product_id: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BrewfatherMisc {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_: Option<String>,
+    #[serde(rename = "use")]
+    pub use_: Option<String>,
+    pub time: Option<Minutes>,
+    pub amount: Option<f32>,
+    /// "g", "ml" or "items"
+    pub unit: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BrewfatherEquipment {
+    pub name: Option<String>,
+    /// Wort left in the mash tun, l
+    pub mash_tun_dead_space: Option<Liters>,
+    /// l/kg
+    pub grain_absorption_rate: Option<f32>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct BrewfatherMash {
+    #[serde(default)]
+    pub steps: Vec<BrewfatherMashStep>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BrewfatherMashStep {
+    pub name: Option<String>,
+    /// "Infusion", "Temperature" or "Decoction"
+    #[serde(rename = "type")]
+    pub type_: Option<String>,
+    pub step_temp: Celsius,
+    pub step_time: Option<Minutes>,
+    pub ramp_time: Option<Minutes>,
+}
+
+impl From<BrewfatherRecipe> for Recipe<BrewfatherSrc> {
+    fn from(bf: BrewfatherRecipe) -> Self {
+        let boil_time = bf.boil_time.unwrap_or(60.0);
+        Recipe {
+            name: bf.name,
+            type_: recipe_type(bf.type_.as_deref()),
+            style: bf.style.map_or_else(unknown_style, style),
+            brewer: bf.author.unwrap_or_default(),
+            asst_brewer: None,
+            equipment: None,
+            batch_size: bf.batch_size,
+            pre_boil_gravity: None,
+            og: bf.og,
+            fg: bf.fg,
+            efficiency: bf.efficiency.unwrap_or(DEFAULT_EFFICIENCY),
+            hops: bf.hops.into_iter().map(hop).collect(),
+            fermentables: bf.fermentables.into_iter().map(fermentable).collect(),
+            yeasts: bf.yeasts.into_iter().map(yeast).collect(),
+            waters: Vec::new(),
+            miscs: bf.miscs.into_iter().map(misc).collect(),
+            mash: process::Mash::from_steps(bf.mash.map_or_else(Vec::new, |mash| {
+                mash.steps.into_iter().map(mash_step).collect()
+            })),
+            boil: process::Boil::from_beerxml_recipe(
+                bf.boil_size.unwrap_or(bf.batch_size),
+                boil_time,
+            ),
+            fermentation: process::Fermentation {},
+            carbonation: process::Carbonation {},
+            notes: bf.notes,
+            taste_notes: None,
+            taste_rating: None,
+            date: None,
+            ibu_method: ibu_method(bf.ibu_formula.as_deref()),
+            hop_storage: None,
+            system: bf.equipment.map_or_else(System::traditional, system),
+            recipe_src: BrewfatherSrc {
+                id: bf.id,
+                ibu_formula: bf.ibu_formula,
+            },
+        }
+    }
+}
+
+/// Original values in the Brewfather source recipe
+#[derive(Debug, Clone)]
+pub struct BrewfatherSrc {
+    /// Brewfather recipe id
+    pub id: Option<String>,
+    pub ibu_formula: Option<String>,
+}
+
+impl RecipeSrc for BrewfatherSrc {}
+
+fn recipe_type(type_: Option<&str>) -> beerxml::Type {
+    match type_ {
+        Some("Extract") => beerxml::Type::Extract,
+        Some("Partial Mash") => beerxml::Type::PartialMash,
+        _ => beerxml::Type::AllGrain,
+    }
+}
+
+fn ibu_method(formula: Option<&str>) -> ibu::Method {
+    match formula {
+        Some("rager") => ibu::Method::Rager,
+        Some("garetz") => ibu::Method::Garetz,
+        _ => ibu::Method::default(),
+    }
+}
+
+fn style(bf: BrewfatherStyle) -> beerxml::Style {
+    beerxml::Style {
+        name: bf.name.unwrap_or_default(),
+        category: bf.category.unwrap_or_default(),
+        version: 1,
+        category_number: match bf.category_number {
+            Some(Value::String(number)) => number,
+            Some(number) => number.to_string(),
+            None => String::new(),
+        },
+        style_letter: bf.style_letter.unwrap_or_default(),
+        style_guide: bf.style_guide.unwrap_or_default(),
+        type_: match bf.type_.as_deref() {
+            Some("Lager") => beerxml::style::Type::Lager,
+            Some("Mead") => beerxml::style::Type::Mead,
+            Some("Wheat") => beerxml::style::Type::Wheat,
+            Some("Mixed") => beerxml::style::Type::Mixed,
+            Some("Cider") => beerxml::style::Type::Cider,
+            _ => beerxml::style::Type::Ale,
+        },
+        og_min: bf.og_min.unwrap_or(1.0),
+        og_max: bf.og_max.unwrap_or(1.0),
+        fg_min: bf.fg_min.unwrap_or(1.0),
+        fg_max: bf.fg_max.unwrap_or(1.0),
+        ibu_min: bf.ibu_min.unwrap_or(0.0),
+        ibu_max: bf.ibu_max.unwrap_or(0.0),
+        color_min: bf.color_min.unwrap_or(0.0),
+        color_max: bf.color_max.unwrap_or(0.0),
+        carb_min: bf.carb_min,
+        carb_max: bf.carb_max,
+        abv_min: bf.abv_min,
+        abv_max: bf.abv_max,
+        notes: None,
+        profile: None,
+        ingredients: None,
+        examples: None,
+    }
+}
+
+fn unknown_style() -> beerxml::Style {
+    style(BrewfatherStyle {
+        name: None,
+        category: None,
+        category_number: None,
+        style_letter: None,
+        style_guide: None,
+        type_: None,
+        og_min: None,
+        og_max: None,
+        fg_min: None,
+        fg_max: None,
+        ibu_min: None,
+        ibu_max: None,
+        color_min: None,
+        color_max: None,
+        carb_min: None,
+        carb_max: None,
+        abv_min: None,
+        abv_max: None,
+    })
+}
+
+fn fermentable(bf: BrewfatherFermentable) -> beerxml::Fermentable {
+    use beerxml::fermentable::Type;
+    let type_ = match bf.type_.as_deref() {
+        Some("Sugar") => Type::Sugar,
+        Some("Extract") => Type::Extract,
+        Some("Dry Extract") => Type::DryExtract,
+        Some("Adjunct") => Type::Adjunct,
+        _ => Type::Grain,
+    };
+    let mut fermentable = beerxml::Fermentable::new(
+        bf.name,
+        type_,
+        bf.amount,
+        bf.potential_percentage.unwrap_or(0.0),
+        bf.color.unwrap_or(0.0),
+    );
+    fermentable.add_after_boil = match bf.add_after_boil {
+        Some(Value::Bool(add_after_boil)) => Some(add_after_boil),
+        Some(Value::String(add_after_boil)) => add_after_boil.parse().ok(),
+        _ => None,
+    };
+    fermentable
+}
+
+fn hop(bf: BrewfatherHop) -> beerxml::Hop {
+    use beerxml::hop::{Form, Use};
+    let use_ = match bf.use_.as_deref() {
+        Some("Dry Hop") => Use::DryHop,
+        Some("Mash") => Use::Mash,
+        Some("First Wort") => Use::FirstWort,
+        Some("Aroma") => Use::Aroma,
+        _ => Use::Boil,
+    };
+    let time = bf.time.unwrap_or(0.0);
+    let time = if use_ == Use::DryHop {
+        time * MINUTES_PER_DAY
+    } else {
+        time
+    };
+    let mut hop = beerxml::Hop::new(bf.name, bf.alpha, bf.amount / 1000.0, use_, time);
+    hop.form = match bf.type_.as_deref() {
+        Some("Pellet") => Some(Form::Pellet),
+        Some("Leaf") => Some(Form::Leaf),
+        Some("Plug") => Some(Form::Plug),
+        _ => None,
+    };
+    hop
+}
+
+fn yeast(bf: BrewfatherYeast) -> beerxml::Yeast {
+    use beerxml::yeast::{Form, Type};
+    let type_ = match bf.type_.as_deref() {
+        Some("Lager") => Type::Lager,
+        Some("Wheat") => Type::Wheat,
+        Some("Wine") => Type::Wine,
+        Some("Champagne") => Type::Champagne,
+        _ => Type::Ale,
+    };
+    let form = match bf.form.as_deref() {
+        Some("Dry") => Form::Dry,
+        Some("Slant") => Form::Slant,
+        Some("Culture") => Form::Culture,
+        _ => Form::Liquid,
+    };
+    let amount = bf.amount.unwrap_or(0.0);
+    let (amount, amount_is_weight) = match bf.unit.as_deref() {
+        Some("g") => (amount / 1000.0, Some(true)),
+        Some("ml") => (amount / 1000.0, None),
+        _ => (amount, None),
+    };
+    let mut yeast = beerxml::Yeast::new(bf.name, type_, form, amount);
+    yeast.amount_is_weight = amount_is_weight;
+    yeast.product_id = bf.product_id;
+    yeast
+}
+
+fn misc(bf: BrewfatherMisc) -> beerxml::Misc {
+    use beerxml::misc::{Type, Use};
+    let type_ = match bf.type_.as_deref() {
+        Some("Spice") => Type::Spice,
+        Some("Fining") => Type::Fining,
+        Some("Water Agent") => Type::WaterAgent,
+        Some("Herb") => Type::Herb,
+        Some("Flavor") => Type::Flavor,
+        _ => Type::Other,
+    };
+    let use_ = match bf.use_.as_deref() {
+        Some("Mash") | Some("Sparge") => Use::Mash,
+        Some("Primary") => Use::Primary,
+        Some("Secondary") => Use::Secondary,
+        Some("Bottling") => Use::Bottling,
+        _ => Use::Boil,
+    };
+    let amount = bf.amount.unwrap_or(0.0);
+    let amount = match bf.unit.as_deref() {
+        Some("g") | Some("ml") => amount / 1000.0,
+        _ => amount,
+    };
+    beerxml::Misc::new(bf.name, type_, use_, bf.time.unwrap_or(0.0), amount)
+}
+
+/// Traditional system with the deadspace and grain absorption of the equipment profile
+fn system(bf: BrewfatherEquipment) -> System {
+    let traditional = System::traditional();
+    System {
+        grain_absorption: bf
+            .grain_absorption_rate
+            .unwrap_or(traditional.grain_absorption),
+        lauter_deadspace: bf.mash_tun_dead_space.unwrap_or(0.0),
+        ..traditional
+    }
+}
+
+fn mash_step(bf: BrewfatherMashStep) -> beerxml::mash::MashStep {
+    beerxml::mash::MashStep {
+        name: bf.name.unwrap_or_default(),
+        version: 1,
+        type_: match bf.type_.as_deref() {
+            Some("Infusion") => beerxml::mash::Type::Infusion,
+            Some("Decoction") => beerxml::mash::Type::Decoction,
+            _ => beerxml::mash::Type::Temperature,
+        },
+        infuse_amount: None,
+        step_temp: bf.step_temp,
+        step_time: bf.step_time.unwrap_or_default(),
+        // A ramp time of 0 is Brewfather's default for not given
+        ramp_time: bf.ramp_time.filter(|time| *time > 0.0),
+        end_temp: None,
+    }
+}
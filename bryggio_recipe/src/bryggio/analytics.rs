@@ -0,0 +1,165 @@
+//! # Analytics
+//!
+//! Statistics over a collection of recipes, such as a recipe archive.
+use crate::bryggio::recipe::{Recipe, RecipeSrc};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// Summary of a distribution of values
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+pub struct Distribution {
+    pub count: usize,
+    pub min: f32,
+    pub max: f32,
+    pub mean: f32,
+    pub median: f32,
+}
+
+impl Distribution {
+    /// Distribution of `values`, ignoring `NaN`s.
+    ///
+    /// `None` if there are no values.
+    pub fn new(values: &[f32]) -> Option<Self> {
+        let mut values: Vec<f32> = values.iter().copied().filter(|x| !x.is_nan()).collect();
+        if values.is_empty() {
+            return None;
+        }
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let count = values.len();
+        let median = (values[(count - 1) / 2] + values[count / 2]) / 2.0;
+        Some(Self {
+            count,
+            min: values[0],
+            max: values[count - 1],
+            mean: values.iter().sum::<f32>() / count as f32,
+            median,
+        })
+    }
+}
+
+/// Number of recipes an ingredient is used in
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct Usage {
+    pub name: String,
+    pub recipes: usize,
+}
+
+/// Statistics over a collection of recipes
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct RecipeStats {
+    pub recipes: usize,
+    pub ibu: Option<Distribution>,
+    pub og: Option<Distribution>,
+    /// Color in SRM
+    pub color: Option<Distribution>,
+    /// Hop rate in g/l of all additions of a recipe, by hop use
+    pub hop_rates: BTreeMap<String, Distribution>,
+    /// Share of the grist in percent of weight, by fermentable name
+    pub grain_bill: BTreeMap<String, Distribution>,
+    /// Yeasts, most used first
+    pub yeasts: Vec<Usage>,
+    /// Hops, most used first
+    pub hops: Vec<Usage>,
+    /// Fermentables, most used first
+    pub fermentables: Vec<Usage>,
+    /// Miscs, most used first
+    pub miscs: Vec<Usage>,
+}
+
+impl RecipeStats {
+    pub fn new<'a, Src, I>(recipes: I) -> Self
+    where
+        Src: RecipeSrc + 'a,
+        I: IntoIterator<Item = &'a Recipe<Src>>,
+    {
+        let mut count = 0;
+        let mut ibu = Vec::new();
+        let mut og = Vec::new();
+        let mut color = Vec::new();
+        let mut hop_rates: HashMap<String, Vec<f32>> = HashMap::new();
+        let mut grain_bill: HashMap<String, Vec<f32>> = HashMap::new();
+        let mut yeasts = HashMap::new();
+        let mut hops = HashMap::new();
+        let mut fermentables = HashMap::new();
+        let mut miscs = HashMap::new();
+        for recipe in recipes {
+            count += 1;
+            ibu.push(recipe.ibu());
+            og.push(recipe.original_gravity().specific_gravity());
+            color.push(recipe.estimated_color());
+            let mut recipe_hop_rates: HashMap<String, f32> = HashMap::new();
+            for hop in recipe.hops() {
+                *recipe_hop_rates.entry(hop.use_.to_string()).or_default() +=
+                    hop.amount * 1000.0 / recipe.batch_size;
+            }
+            for (use_, rate) in recipe_hop_rates {
+                hop_rates.entry(use_).or_default().push(rate);
+            }
+            let grist: f32 = recipe.fermentables().map(|f| f.amount).sum();
+            for fermentable in recipe.fermentables() {
+                grain_bill
+                    .entry(fermentable.name.clone())
+                    .or_default()
+                    .push(100.0 * fermentable.amount / grist);
+            }
+            count_usage(&mut yeasts, recipe.yeasts().map(|yeast| &yeast.name));
+            count_usage(&mut hops, recipe.hops().map(|hop| &hop.name));
+            count_usage(&mut fermentables, recipe.fermentables().map(|f| &f.name));
+            count_usage(&mut miscs, recipe.miscs().map(|misc| &misc.name));
+        }
+        Self {
+            recipes: count,
+            ibu: Distribution::new(&ibu),
+            og: Distribution::new(&og),
+            color: Distribution::new(&color),
+            hop_rates: distributions(hop_rates),
+            grain_bill: distributions(grain_bill),
+            yeasts: most_used(yeasts),
+            hops: most_used(hops),
+            fermentables: most_used(fermentables),
+            miscs: most_used(miscs),
+        }
+    }
+}
+
+/// Counts every ingredient name once per recipe
+fn count_usage<'a, I: Iterator<Item = &'a String>>(usage: &mut HashMap<String, usize>, names: I) {
+    let mut names: Vec<&String> = names.collect();
+    names.sort();
+    names.dedup();
+    for name in names {
+        *usage.entry(name.clone()).or_default() += 1;
+    }
+}
+
+fn distributions(values: HashMap<String, Vec<f32>>) -> BTreeMap<String, Distribution> {
+    values
+        .into_iter()
+        .filter_map(|(key, values)| Distribution::new(&values).map(|dist| (key, dist)))
+        .collect()
+}
+
+fn most_used(usage: HashMap<String, usize>) -> Vec<Usage> {
+    let mut usage: Vec<Usage> = usage
+        .into_iter()
+        .map(|(name, recipes)| Usage { name, recipes })
+        .collect();
+    usage.sort_by(|a, b| b.recipes.cmp(&a.recipes).then_with(|| a.name.cmp(&b.name)));
+    usage
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distribution() {
+        let dist = Distribution::new(&[3.0, 1.0, f32::NAN, 4.0, 2.0]).unwrap();
+        assert_eq!(dist.count, 4);
+        assert_eq!(dist.min, 1.0);
+        assert_eq!(dist.max, 4.0);
+        assert_eq!(dist.mean, 2.5);
+        assert_eq!(dist.median, 2.5);
+        assert!(Distribution::new(&[]).is_none());
+    }
+}
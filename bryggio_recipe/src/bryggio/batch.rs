@@ -0,0 +1,193 @@
+//! # Batch
+//!
+//! Brew log of a single batch of a recipe.
+//!
+//! Records the values measured during the brew day and fermentation,
+//! and compares them against the predictions of the recipe.
+use crate::bryggio::analytics::Distribution;
+use crate::bryggio::efficiency::Readings;
+use crate::bryggio::quantity::Gravity;
+use crate::bryggio::recipe::{Recipe, RecipeSrc};
+use brew_calculator::units::*;
+use serde::{Deserialize, Serialize};
+
+/// Temperature reading during fermentation
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct TemperatureReading {
+    /// Days since pitching
+    pub day: Days,
+    pub temp: Celsius,
+}
+
+/// Brew log of a batch
+///
+/// All measurements are optional, a batch is filled in as brewing progresses.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct Batch {
+    /// Name of the brewed recipe
+    pub recipe: String,
+    pub brew_date: Option<String>,
+    pub pre_boil_gravity: Option<SpecificGravity>,
+    pub pre_boil_volume: Option<Liters>,
+    pub og: Option<SpecificGravity>,
+    /// Volume into the fermenter
+    pub batch_volume: Option<Liters>,
+    pub fg: Option<SpecificGravity>,
+    pub mash_ph: Option<PH>,
+    #[serde(default)]
+    pub fermentation_temps: Vec<TemperatureReading>,
+    pub packaging_date: Option<String>,
+    pub notes: Option<String>,
+}
+
+/// Measured value and the recipe's prediction
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+pub struct Comparison {
+    pub predicted: f32,
+    pub actual: f32,
+}
+
+impl Comparison {
+    fn new(predicted: f32, actual: f32) -> Self {
+        Self { predicted, actual }
+    }
+
+    /// Actual minus predicted value
+    pub fn difference(self) -> f32 {
+        self.actual - self.predicted
+    }
+}
+
+/// Comparison of a batch against its recipe
+///
+/// A comparison is `None` when the needed measurements are missing.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+pub struct BatchReport {
+    pub pre_boil_gravity: Option<Comparison>,
+    pub pre_boil_volume: Option<Comparison>,
+    /// Efficiency into the boil kettle in percent
+    pub pre_boil_efficiency: Option<Comparison>,
+    pub og: Option<Comparison>,
+    /// Brewhouse efficiency in percent
+    pub efficiency: Option<Comparison>,
+    pub fg: Option<Comparison>,
+    /// Apparent attenuation in percent
+    pub attenuation: Option<Comparison>,
+    /// Alcohol by volume from the real extract
+    pub abv: Option<Comparison>,
+    pub fermentation_temp: Option<Distribution>,
+}
+
+impl Batch {
+    /// New empty brew log for `recipe`
+    pub fn new<Src: RecipeSrc>(recipe: &Recipe<Src>, brew_date: Option<String>) -> Self {
+        Self {
+            recipe: recipe.name.clone(),
+            brew_date,
+            ..Self::default()
+        }
+    }
+
+    /// Efficiency into the boil kettle, from the pre-boil gravity and volume
+    ///
+    /// `None` for recipes without mashed fermentables.
+    pub fn pre_boil_efficiency<Src: RecipeSrc>(&self, recipe: &Recipe<Src>) -> Option<Percent> {
+        recipe.efficiency_breakdown(&self.readings()).mash
+    }
+
+    /// Brewhouse efficiency, from the original gravity and the volume into the fermenter
+    ///
+    /// Uses the recipe's batch size if the volume was not measured.
+    /// `None` for recipes without mashed fermentables.
+    pub fn efficiency<Src: RecipeSrc>(&self, recipe: &Recipe<Src>) -> Option<Percent> {
+        recipe.efficiency_breakdown(&self.readings()).brewhouse
+    }
+
+    /// Readings of the brew day for an [`EfficiencyBreakdown`](crate::bryggio::efficiency::EfficiencyBreakdown)
+    pub fn readings(&self) -> Readings {
+        Readings {
+            pre_boil_gravity: self.pre_boil_gravity,
+            pre_boil_volume: self.pre_boil_volume,
+            og: self.og,
+            batch_volume: self.batch_volume,
+            ..Readings::default()
+        }
+    }
+
+    /// Apparent attenuation in percent
+    pub fn apparent_attenuation(&self) -> Option<Percent> {
+        Some(apparent_attenuation(self.og?, self.fg?))
+    }
+
+    /// Alcohol by volume in percent, corrected for the real extract
+    pub fn real_abv(&self) -> Option<Abv> {
+        Some(real_abv(self.og?, self.fg?))
+    }
+
+    /// Distribution of the fermentation temperature readings
+    pub fn fermentation_temperature(&self) -> Option<Distribution> {
+        let temps: Vec<Celsius> = self
+            .fermentation_temps
+            .iter()
+            .map(|reading| reading.temp)
+            .collect();
+        Distribution::new(&temps)
+    }
+
+    /// Measured values compared against the predictions of `recipe`
+    pub fn report<Src: RecipeSrc>(&self, recipe: &Recipe<Src>) -> BatchReport {
+        let og = recipe.original_gravity().specific_gravity();
+        let fg = recipe.estimated_fg();
+        let compare = |predicted: f32, actual: Option<f32>| {
+            actual.map(|actual| Comparison::new(predicted, actual))
+        };
+        BatchReport {
+            pre_boil_gravity: compare(recipe.estimated_pre_boil_gravity(), self.pre_boil_gravity),
+            pre_boil_volume: compare(recipe.pre_boil_volume().liters(), self.pre_boil_volume),
+            pre_boil_efficiency: compare(recipe.efficiency(), self.pre_boil_efficiency(recipe)),
+            og: compare(og, self.og),
+            efficiency: compare(recipe.efficiency(), self.efficiency(recipe)),
+            fg: compare(fg, self.fg),
+            attenuation: compare(apparent_attenuation(og, fg), self.apparent_attenuation()),
+            abv: compare(real_abv(og, fg), self.real_abv()),
+            fermentation_temp: self.fermentation_temperature(),
+        }
+    }
+}
+
+/// $AA = 100 \frac{OG - FG}{OG - 1}$
+fn apparent_attenuation(og: SpecificGravity, fg: SpecificGravity) -> Percent {
+    100.0 * (og - fg) / (og - 1.0)
+}
+
+/// Alcohol by volume from the real extract
+///
+/// With original and apparent extract $OE$ and $AE$ in °P,
+/// the real extract is $RE = 0.1808 OE + 0.8192 AE$ and
+///
+/// $ABV = \frac{OE - RE}{2.0665 - 0.010665 OE} \cdot \frac{FG}{0.794}$
+fn real_abv(og: SpecificGravity, fg: SpecificGravity) -> Abv {
+    let oe = Gravity::from_specific_gravity(og).plato();
+    let ae = Gravity::from_specific_gravity(fg).plato();
+    let re = 0.1808 * oe + 0.8192 * ae;
+    let abw = (oe - re) / (2.0665 - 0.010665 * oe);
+    abw * fg / 0.794
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attenuation_and_abv() {
+        let batch = Batch {
+            og: Some(1.050),
+            fg: Some(1.010),
+            ..Batch::default()
+        };
+        assert!((batch.apparent_attenuation().unwrap() - 80.0).abs() < 1e-3);
+        let abv = batch.real_abv().unwrap();
+        assert!(abv > 5.2 && abv < 5.4, "{}", abv);
+        assert_eq!(Batch::default().real_abv(), None);
+    }
+}
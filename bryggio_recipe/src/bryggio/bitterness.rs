@@ -0,0 +1,363 @@
+//! # Bitterness
+//!
+//! IBU models extending the boil-only methods in `brew_calculator::ibu` with post-boil
+//! isomerization, first wort and mash hop adjustments and hop form utilization.
+//!
+//! Isomerization and degradation rates are taken from Malowicki and Shellhammer (2005):
+//!
+//! - $k_1(T) = 7.9 \cdot 10^{11} e^{-11858 / T}$
+//! - $k_2(T) = 4.1 \cdot 10^{12} e^{-12994 / T}$
+//!
+//! with $T$ in Kelvin and rates in $\text{min}^{-1}$.
+//!
+//! Alpha acid loss during hop storage follows Garetz (1994), as a first order decay
+//! with the rate given by the Hop Stability Index.
+use crate::bryggio::Hop;
+use beerxml::hop::{Form, Use};
+use brew_calculator::ibu::{self, IbuCalc, Tinseth};
+use brew_calculator::units::*;
+
+/// Utilization of a first wort hop relative to a boil addition of the full boil time
+const FIRST_WORT_FACTOR: f32 = 1.1;
+/// Utilization of a mash hop relative to a boil addition of the full boil time
+const MASH_HOP_FACTOR: f32 = 0.2;
+/// Utilization of pellets relative to leaf hops
+const PELLET_FACTOR: f32 = 1.1;
+/// Fraction of the isomerized alpha acids in the wort that survive into the finished beer
+const IAA_SURVIVAL: f32 = 0.6;
+/// Oxidized alpha acids and other non-isomerized bittering compounds, as a fraction of the
+/// added alpha acids, that survive into the finished beer
+const NON_IAA_FRACTION: f32 = 0.022;
+/// Conversion from bittering compounds in mg/l to IBU
+const IBU_PER_MG_PER_L: f32 = 5.0 / 7.0;
+const BOILING_POINT: Celsius = 100.0;
+/// Storage time over which the Hop Stability Index is measured, 6 months
+const HSI_AGE: Days = 182.5;
+/// Storage temperature at which the Hop Stability Index is measured
+const HSI_TEMP: Celsius = 20.0;
+/// Temperature increase that doubles the rate of alpha acid loss
+const AGING_DOUBLING_TEMP: Celsius = 15.0;
+const KELVIN_OFFSET: f32 = 273.15;
+
+/// IBU model
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Model {
+    /// Boil additions only, with a method from `brew_calculator`.
+    ///
+    /// Aroma and dry hops do not contribute.
+    Boil(ibu::Method),
+    /// Modified IBU
+    ///
+    /// Tinseth, where the boil time is extended with the isomerization that continues during
+    /// the hop stand, scaled by the isomerization rate at the hop stand temperature.
+    /// Aroma hops contribute from their steep time in the hop stand.
+    MIbu(HopStand),
+    /// Simplified SMPH
+    ///
+    /// Models the isomerized alpha acids from the isomerization kinetics and adds the
+    /// non-isomerized bittering compounds, which also makes dry hops contribute.
+    Smph(HopStand),
+}
+
+/// Whirlpool or hop stand after flameout
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct HopStand {
+    /// Time from flameout until the wort is chilled
+    pub time: Minutes,
+    /// Average wort temperature during the hop stand
+    pub temp: Celsius,
+}
+
+impl Default for HopStand {
+    fn default() -> Self {
+        Self {
+            time: 0.0,
+            temp: 80.0,
+        }
+    }
+}
+
+impl HopStand {
+    /// Boil time equivalent of the hop stand
+    fn equivalent_time(&self) -> Minutes {
+        self.time * relative_isomerization_rate(self.temp)
+    }
+}
+
+/// Storage conditions of hops since harvest or packaging
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct HopStorage {
+    pub temp: Celsius,
+    pub packaging: Packaging,
+    pub age: Days,
+}
+
+/// Hop packaging, limiting the oxygen available to the alpha acids
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Packaging {
+    /// Not sealed, stored in air
+    Open,
+    /// Loosely sealed
+    Loose,
+    /// Vacuum packed or nitrogen flushed
+    Sealed,
+}
+
+impl Packaging {
+    /// Rate of alpha acid loss relative to storage in air
+    pub fn storage_factor(self) -> f32 {
+        match self {
+            Packaging::Open => 1.0,
+            Packaging::Loose => 0.75,
+            Packaging::Sealed => 0.5,
+        }
+    }
+}
+
+impl HopStorage {
+    /// Rate of alpha acid loss relative to storage in air at 20 °C
+    fn relative_rate(&self) -> f32 {
+        2f32.powf((self.temp - HSI_TEMP) / AGING_DOUBLING_TEMP) * self.packaging.storage_factor()
+    }
+}
+
+/// Alpha acids of `hop` after `storage`
+///
+/// $\alpha = \alpha_0 e^{-k \cdot TF \cdot SF \cdot t}$, where $k = -\ln(1 - HSI) / 6$ months,
+/// the temperature factor $TF$ doubles every 15 °C above 20 °C
+/// and $SF$ is the storage factor of the packaging.
+///
+/// Hops without a Hop Stability Index keep their alpha acids.
+pub fn aged_alpha(hop: &Hop, storage: &HopStorage) -> Percent {
+    match hop.hsi {
+        Some(hsi) if hsi > 0.0 && hsi < 100.0 => {
+            let k = -(1.0 - hsi / 100.0).ln() / HSI_AGE;
+            hop.alpha * (-k * storage.relative_rate() * storage.age).exp()
+        }
+        _ => hop.alpha,
+    }
+}
+
+/// Bitterness of a recipe, broken down per hop addition
+#[derive(Clone, Debug, PartialEq)]
+pub struct IbuReport {
+    pub model: Model,
+    pub hops: Vec<HopContribution>,
+    /// Total IBU
+    pub ibu: Ibu,
+    /// Bitterness to original gravity ratio, BU:GU
+    pub bu_gu: f32,
+}
+
+impl IbuReport {
+    pub(crate) fn new(model: Model, hops: Vec<HopContribution>, og: SpecificGravity) -> Self {
+        let ibu = hops.iter().map(|hop| hop.ibu).sum();
+        Self {
+            model,
+            hops,
+            ibu,
+            bu_gu: bu_gu(ibu, og),
+        }
+    }
+}
+
+/// IBU contribution from a single hop addition
+#[derive(Clone, Debug, PartialEq)]
+pub struct HopContribution {
+    pub name: String,
+    pub use_: Use,
+    pub time: Minutes,
+    /// Average wort volume used in the calculation
+    pub volume: Liters,
+    /// Average specific gravity used in the calculation
+    pub gravity: SpecificGravity,
+    /// Share of the added alpha acids that end up as IBU
+    pub utilization: Percent,
+    pub ibu: Ibu,
+}
+
+impl HopContribution {
+    pub(crate) fn new(hop: &Hop, volume: Liters, gravity: SpecificGravity, ibu: Ibu) -> Self {
+        let alpha_acids = alpha_acid_concentration(hop, volume);
+        let utilization = if alpha_acids > 0.0 {
+            100.0 * ibu / alpha_acids
+        } else {
+            0.0
+        };
+        Self {
+            name: hop.name.clone(),
+            use_: hop.use_,
+            time: hop.time,
+            volume,
+            gravity,
+            utilization,
+            ibu,
+        }
+    }
+}
+
+/// Bitterness to original gravity ratio
+///
+/// $\frac{IBU}{1000 (OG - 1)}$
+pub fn bu_gu(ibu: Ibu, og: SpecificGravity) -> f32 {
+    ibu / (1000.0 * (og - 1.0))
+}
+
+impl Model {
+    /// IBU contribution from a single hop addition
+    ///
+    /// - `boil_time`: total boil time
+    /// - `volume`: average wort volume during the time the hop is in the wort
+    /// - `gravity`: average specific gravity during the time the hop is in the wort
+    pub fn ibu(
+        &self,
+        hop: &Hop,
+        boil_time: Minutes,
+        volume: Liters,
+        gravity: SpecificGravity,
+    ) -> Ibu {
+        let factor = use_factor(hop.use_) * form_factor(hop.form);
+        match self {
+            Model::Boil(method) => {
+                if hop.bittering() {
+                    factor
+                        * method.ibu(
+                            hop.amount,
+                            hop.alpha,
+                            volume,
+                            time_in_boil(hop, boil_time),
+                            gravity,
+                        )
+                } else {
+                    0.0
+                }
+            }
+            Model::MIbu(hop_stand) => match isomerization_time(hop, boil_time, hop_stand) {
+                Some(time) => {
+                    factor
+                        * ibu::Method::Tinseth(Tinseth {})
+                            .ibu(hop.amount, hop.alpha, volume, time, gravity)
+                }
+                None => 0.0,
+            },
+            Model::Smph(hop_stand) => {
+                let alpha_acids = alpha_acid_concentration(hop, volume);
+                let iaa = isomerization_time(hop, boil_time, hop_stand).map_or(0.0, |time| {
+                    isomerized_fraction(BOILING_POINT, time) * alpha_acids * IAA_SURVIVAL
+                });
+                let non_iaa = alpha_acids * NON_IAA_FRACTION;
+                factor * IBU_PER_MG_PER_L * (iaa + non_iaa)
+            }
+        }
+    }
+}
+
+/// Time in the boil
+///
+/// First wort and mash hops are present during the full boil.
+/// Aroma and dry hops are added after the boil.
+pub fn time_in_boil(hop: &Hop, boil_time: Minutes) -> Minutes {
+    match hop.use_ {
+        Use::FirstWort | Use::Mash => boil_time,
+        Use::Boil => hop.time.min(boil_time),
+        Use::Aroma | Use::DryHop => 0.0,
+    }
+}
+
+/// Boil time equivalent during which the hop isomerizes, including the hop stand.
+///
+/// `None` for dry hops, which are never added to hot wort.
+fn isomerization_time(hop: &Hop, boil_time: Minutes, hop_stand: &HopStand) -> Option<Minutes> {
+    match hop.use_ {
+        Use::Boil | Use::FirstWort | Use::Mash => {
+            Some(time_in_boil(hop, boil_time) + hop_stand.equivalent_time())
+        }
+        Use::Aroma => {
+            Some(hop.time.min(hop_stand.time) * relative_isomerization_rate(hop_stand.temp))
+        }
+        Use::DryHop => None,
+    }
+}
+
+/// Utilization relative to a regular boil addition
+pub fn use_factor(use_: Use) -> f32 {
+    match use_ {
+        Use::FirstWort => FIRST_WORT_FACTOR,
+        Use::Mash => MASH_HOP_FACTOR,
+        Use::Boil | Use::Aroma | Use::DryHop => 1.0,
+    }
+}
+
+/// Utilization relative to leaf hops
+pub fn form_factor(form: Option<Form>) -> f32 {
+    match form {
+        Some(Form::Pellet) => PELLET_FACTOR,
+        Some(Form::Plug) | Some(Form::Leaf) | None => 1.0,
+    }
+}
+
+/// Isomerization rate at `temp` relative to the rate at boiling
+pub fn relative_isomerization_rate(temp: Celsius) -> f32 {
+    isomerization_rate(temp) / isomerization_rate(BOILING_POINT)
+}
+
+/// Isomerization rate $k_1$
+fn isomerization_rate(temp: Celsius) -> f32 {
+    7.9e11 * (-11858.0 / (temp + KELVIN_OFFSET)).exp()
+}
+
+/// Degradation rate of isomerized alpha acids $k_2$
+fn degradation_rate(temp: Celsius) -> f32 {
+    4.1e12 * (-12994.0 / (temp + KELVIN_OFFSET)).exp()
+}
+
+/// Fraction of the added alpha acids that are isomerized after `time` minutes at `temp`
+///
+/// $\frac{k_1}{k_2 - k_1} (e^{-k_1 t} - e^{-k_2 t})$
+fn isomerized_fraction(temp: Celsius, time: Minutes) -> f32 {
+    let k_1 = isomerization_rate(temp);
+    let k_2 = degradation_rate(temp);
+    k_1 / (k_2 - k_1) * ((-k_1 * time).exp() - (-k_2 * time).exp())
+}
+
+/// Concentration of added alpha acids in mg/l
+fn alpha_acid_concentration(hop: &Hop, volume: Liters) -> f32 {
+    hop.alpha / 100.0 * hop.amount * 1.0e6 / volume
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn isomerization_slows_down_below_boiling() {
+        assert_eq!(relative_isomerization_rate(BOILING_POINT), 1.0);
+        let whirlpool = relative_isomerization_rate(80.0);
+        assert!(whirlpool > 0.1 && whirlpool < 0.2, "{}", whirlpool);
+    }
+
+    #[test]
+    fn hsi_is_the_loss_after_six_months_in_air() {
+        let mut hop = Hop::new("Cascade".into(), 10.0, 0.03, Use::Boil, 60.0);
+        let mut storage = HopStorage {
+            temp: HSI_TEMP,
+            packaging: Packaging::Open,
+            age: HSI_AGE,
+        };
+        assert_eq!(aged_alpha(&hop, &storage), 10.0);
+        hop.hsi = Some(40.0);
+        assert!((aged_alpha(&hop, &storage) - 6.0).abs() < 1e-4);
+        // A freezer at -20 °C with sealed bags slows the loss about 13 times
+        storage.temp = -20.0;
+        storage.packaging = Packaging::Sealed;
+        let frozen = aged_alpha(&hop, &storage);
+        assert!(frozen > 9.6 && frozen < 9.7, "{}", frozen);
+    }
+
+    #[test]
+    fn isomerized_fraction_of_an_hour_long_boil() {
+        let fraction = isomerized_fraction(BOILING_POINT, 60.0);
+        assert!(fraction > 0.4 && fraction < 0.5, "{}", fraction);
+        assert_eq!(isomerized_fraction(BOILING_POINT, 0.0), 0.0);
+    }
+}
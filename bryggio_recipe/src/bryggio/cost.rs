@@ -0,0 +1,297 @@
+//! # Cost
+//!
+//! Price of brewing a batch of a recipe.
+//!
+//! Ingredients are priced by kind and name, ignoring case, from a price list.
+//! Prices per kg or l only apply to ingredients measured in the same unit.
+//! Water is priced per liter and the energy for heating the wort and boiling
+//! is estimated from the boil volume and time.
+use crate::bryggio::ingredient::Kind;
+use crate::bryggio::inventory;
+use crate::bryggio::quantity::{Mass, Volume};
+use crate::bryggio::recipe::{Recipe, RecipeSrc};
+use brew_calculator::units::*;
+use serde::{de, Deserialize, Deserializer, Serialize};
+
+/// Specific heat of wort, kJ/(kg K)
+const SPECIFIC_HEAT: f32 = 4.186;
+const BOILING_POINT: Celsius = 100.0;
+const KILOJOULES_PER_KILOWATT_HOUR: f32 = 3600.0;
+
+/// Price of an ingredient
+///
+/// Amounts are in the units of BeerXML amounts: kg, l or a count.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Price {
+    PerKilogram(f32),
+    PerLiter(f32),
+    /// Price of a pack of `size` kg, l or items
+    ///
+    /// Only whole packs can be bought.
+    PerPack {
+        #[serde(deserialize_with = "positive")]
+        size: f32,
+        price: f32,
+    },
+}
+
+fn positive<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f32, D::Error> {
+    let size = f32::deserialize(deserializer)?;
+    if size > 0.0 {
+        Ok(size)
+    } else {
+        Err(de::Error::custom(format!(
+            "pack size must be positive, got {}",
+            size
+        )))
+    }
+}
+
+impl Price {
+    /// Price of `mass` of the ingredient, e.g. per pound
+    pub fn per_mass(price: f32, mass: Mass) -> Self {
+        Price::PerKilogram(price / mass.kilograms())
+    }
+
+    /// Price of `volume` of the ingredient, e.g. per gallon
+    pub fn per_volume(price: f32, volume: Volume) -> Self {
+        Price::PerLiter(price / volume.liters())
+    }
+
+    /// Price of a pack of `size` kg, l or items, `None` unless the size is positive
+    pub fn per_pack(size: f32, price: f32) -> Option<Self> {
+        if size > 0.0 {
+            Some(Price::PerPack { size, price })
+        } else {
+            None
+        }
+    }
+
+    /// True if the price applies to an amount in kg (`is_weight`) or l
+    ///
+    /// Packs apply to either, but only if their size is positive.
+    fn applies_to(self, is_weight: bool) -> bool {
+        match self {
+            Price::PerKilogram(_) => is_weight,
+            Price::PerLiter(_) => !is_weight,
+            Price::PerPack { size, .. } => size > 0.0,
+        }
+    }
+
+    /// Price of one kg, l or item, with packs at their price per unit
+    pub fn unit_price(self) -> f32 {
+        match self {
+            Price::PerKilogram(price) | Price::PerLiter(price) => price,
+            Price::PerPack { size, price } => price / size,
+        }
+    }
+
+    /// Cost of using `amount` kg, l or items
+    pub fn cost(self, amount: f32) -> f32 {
+        match self {
+            Price::PerKilogram(price) | Price::PerLiter(price) => price * amount,
+            Price::PerPack { size, price } => (amount / size).ceil() * price,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct IngredientPrice {
+    pub kind: Kind,
+    pub name: String,
+    pub price: Price,
+}
+
+/// Price of electricity or gas, and the heating of the kettle
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct EnergyPrice {
+    /// Price per kWh
+    pub price: f32,
+    /// Power needed to keep a rolling boil, kW
+    pub boil_power: f32,
+    /// Temperature of the wort when heating to a boil starts
+    pub start_temp: Celsius,
+    /// Share of the energy that ends up in the wort
+    pub efficiency: Percent,
+}
+
+impl EnergyPrice {
+    pub fn new(price: f32, boil_power: f32) -> Self {
+        Self {
+            price,
+            boil_power,
+            start_temp: 20.0,
+            efficiency: 100.0,
+        }
+    }
+
+    /// Energy used to heat `volume` to a boil and to boil it for `boil_time`, in kWh
+    ///
+    /// $E = \frac{1}{\eta} \left( \frac{c V (100 - T_0)}{3600} + P t \right)$
+    pub fn energy(&self, volume: Liters, boil_time: Minutes) -> f32 {
+        let heating = SPECIFIC_HEAT * volume * (BOILING_POINT - self.start_temp).max(0.0)
+            / KILOJOULES_PER_KILOWATT_HOUR;
+        let boil = self.boil_power * boil_time / 60.0;
+        (heating + boil) * 100.0 / self.efficiency
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct PriceList {
+    #[serde(default)]
+    pub ingredients: Vec<IngredientPrice>,
+    /// Price per liter of brewing water
+    pub water: Option<f32>,
+    pub energy: Option<EnergyPrice>,
+}
+
+impl PriceList {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, kind: Kind, name: String, price: Price) {
+        self.ingredients.push(IngredientPrice { kind, name, price });
+    }
+
+    pub fn price(&self, kind: Kind, name: &str) -> Option<Price> {
+        self.ingredients
+            .iter()
+            .find(|ingredient| {
+                ingredient.kind == kind && ingredient.name.eq_ignore_ascii_case(name)
+            })
+            .map(|ingredient| ingredient.price)
+    }
+}
+
+/// Cost of one ingredient, summed over all its additions
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct IngredientCost {
+    pub kind: Kind,
+    pub name: String,
+    /// kg, l or a count
+    pub amount: f32,
+    /// `None` if the ingredient is not in the price list,
+    /// or its price is per kg or l and the amount in the other unit
+    pub cost: Option<f32>,
+}
+
+/// Cost of a batch, broken down per ingredient category
+///
+/// Unpriced ingredients, water and energy count as free.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct Cost {
+    pub ingredients: Vec<IngredientCost>,
+    pub fermentables: f32,
+    pub hops: f32,
+    pub yeasts: f32,
+    pub miscs: f32,
+    pub water: f32,
+    pub energy: f32,
+    pub total: f32,
+    /// Total per liter of finished beer
+    pub per_liter: f32,
+}
+
+impl Cost {
+    pub fn new<Src: RecipeSrc>(recipe: &Recipe<Src>, prices: &PriceList) -> Self {
+        let ingredients: Vec<IngredientCost> = inventory::requirements(recipe)
+            .into_iter()
+            .map(|(kind, name, amount, is_weight)| IngredientCost {
+                cost: prices
+                    .price(kind, &name)
+                    .filter(|price| is_weight.is_some_and(|is_weight| price.applies_to(is_weight)))
+                    .map(|price| price.cost(amount)),
+                kind,
+                name,
+                amount,
+            })
+            .collect();
+        let category = |kind: Kind| -> f32 {
+            ingredients
+                .iter()
+                .filter(|ingredient| ingredient.kind == kind)
+                .filter_map(|ingredient| ingredient.cost)
+                .sum()
+        };
+        let pre_boil_volume = recipe.pre_boil_volume().liters();
+        let water = prices.water.map_or(0.0, |price| {
+            price * (pre_boil_volume + recipe.system().retained(recipe.grain_mass()))
+        });
+        let energy = prices.energy.map_or(0.0, |energy| {
+            energy.price * energy.energy(pre_boil_volume, recipe.boil_time())
+        });
+        let (fermentables, hops, yeasts, miscs) = (
+            category(Kind::Fermentable),
+            category(Kind::Hop),
+            category(Kind::Yeast),
+            category(Kind::Misc),
+        );
+        let total = fermentables + hops + yeasts + miscs + water + energy;
+        Self {
+            ingredients,
+            fermentables,
+            hops,
+            yeasts,
+            miscs,
+            water,
+            energy,
+            total,
+            per_liter: total / recipe.batch_size,
+        }
+    }
+
+    /// Ingredients missing from the price list
+    pub fn unpriced(&self) -> impl Iterator<Item = &IngredientCost> {
+        self.ingredients
+            .iter()
+            .filter(|ingredient| ingredient.cost.is_none())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn whole_packs_are_bought() {
+        let yeast = Price::PerPack {
+            size: 1.0,
+            price: 4.5,
+        };
+        assert_eq!(yeast.cost(1.0), 4.5);
+        assert_eq!(yeast.cost(1.5), 9.0);
+        let hops = Price::PerPack {
+            size: 0.1,
+            price: 5.0,
+        };
+        assert_eq!(hops.cost(0.01), 5.0);
+        assert!((hops.unit_price() - 50.0).abs() < 1e-4);
+        let malt = Price::per_mass(2.0, Mass::from_pounds(1.0));
+        assert!((malt.cost(1.0) - 4.409).abs() < 1e-3);
+    }
+
+    #[test]
+    fn packs_have_a_size() {
+        assert_eq!(Price::per_pack(0.0, 4.5), None);
+        let parsed: Result<Price, _> =
+            serde_json::from_str(r#"{"per_pack": {"size": 0.0, "price": 0.0}}"#);
+        assert!(parsed.is_err());
+        let parsed: Price =
+            serde_json::from_str(r#"{"per_pack": {"size": 0.1, "price": 5.0}}"#).unwrap();
+        assert_eq!(Price::per_pack(0.1, 5.0), Some(parsed));
+        assert!(!Price::PerPack {
+            size: 0.0,
+            price: 4.5
+        }
+        .applies_to(true));
+    }
+
+    #[test]
+    fn energy_to_heat_and_boil() {
+        let energy = EnergyPrice::new(0.2, 3.0);
+        // 30 l from 20 to 100 °C is 2.79 kWh, plus an hour at 3 kW
+        assert!((energy.energy(30.0, 60.0) - 5.79).abs() < 0.01);
+    }
+}
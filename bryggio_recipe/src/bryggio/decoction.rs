@@ -0,0 +1,189 @@
+//! # Decoction
+//!
+//! Volume of thick mash to pull for each decoction step, and its rest and boil schedule.
+//!
+//! The pulled thick mash is rested for saccharification, boiled and returned to the main mash,
+//! which has stayed at the previous step's temperature.
+//! The pull is sized with a heat balance over the heat capacities of the grain and the water,
+//! $C_{rest} (T - T_1) = C_{pull} (T_b - T)$, so that the returned decoction brings the mash
+//! to the step temperature $T$.
+//! Evaporation and heat losses during the decoction are ignored.
+use crate::bryggio::mash::{GRAIN_SPECIFIC_HEAT, GRAIN_VOLUME, WATER_SPECIFIC_HEAT};
+use crate::bryggio::recipe::{Recipe, RecipeSrc};
+use beerxml::mash::Type;
+use brew_calculator::units::*;
+use serde::Serialize;
+use thiserror::Error;
+
+/// Pull volumes are rounded to what can be measured with a scoop and a marked pot
+const PULL_RESOLUTION: Liters = 0.1;
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+pub struct DecoctionParams {
+    /// Water to grain ratio of the thick mash, l/kg
+    pub thick_ratio: f32,
+    /// Saccharification rest of the decoction before boiling
+    pub rest_temp: Celsius,
+    pub rest_time: Minutes,
+    pub boil_time: Minutes,
+    /// Boiling point, lower at altitude
+    pub boil_temp: Celsius,
+}
+
+impl Default for DecoctionParams {
+    fn default() -> Self {
+        Self {
+            thick_ratio: 2.0,
+            rest_temp: 70.0,
+            rest_time: 15.0,
+            boil_time: 15.0,
+            boil_temp: 100.0,
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+pub struct Rest {
+    pub name: &'static str,
+    pub temp: Celsius,
+    pub time: Minutes,
+}
+
+/// Plan for one decoction step
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct Decoction {
+    pub step: String,
+    /// Volume of thick mash to pull, rounded to 0.1 l
+    pub pull: Liters,
+    pub grain: Kilograms,
+    pub water: Liters,
+    /// Rests of the pulled decoction, the last being the boil
+    pub schedule: Vec<Rest>,
+    /// Temperature of the main mash while the decoction is pulled
+    pub start_temp: Celsius,
+    /// Temperature of the main mash after returning the rounded pull
+    pub mash_temp: Celsius,
+}
+
+#[derive(Clone, Debug, PartialEq, Error)]
+pub enum DecoctionError {
+    #[error("No water in the mash before decoction step '{0}'")]
+    NoWater(String),
+    #[error("Decoction step '{step}' can not reach {temp} °C")]
+    Unreachable { step: String, temp: Celsius },
+}
+
+/// Water and grain of a mash, or of a part of it
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Mash {
+    water: Liters,
+    grain: Kilograms,
+}
+
+impl Mash {
+    fn heat_capacity(self) -> f32 {
+        WATER_SPECIFIC_HEAT * self.water + GRAIN_SPECIFIC_HEAT * self.grain
+    }
+
+    fn volume(self) -> Liters {
+        self.water + GRAIN_VOLUME * self.grain
+    }
+
+    /// Thick mash of `volume` at `ratio` l/kg
+    fn thick(volume: Liters, ratio: f32) -> Self {
+        let grain = volume / (ratio + GRAIN_VOLUME);
+        Self {
+            water: grain * ratio,
+            grain,
+        }
+    }
+}
+
+/// Temperature of the mash after returning a decoction
+///
+/// - `mash`: heat capacity of the whole mash, including the decoction, kJ/K
+/// - `pull`: heat capacity of the decoction, kJ/K
+pub fn returned_temp(mash: f32, pull: f32, mash_temp: Celsius, decoction_temp: Celsius) -> Celsius {
+    ((mash - pull) * mash_temp + pull * decoction_temp) / mash
+}
+
+/// Decoctions for the `Decoction` steps of `recipe`, in order
+///
+/// The water in the mash is the sum of the infusions before each step.
+pub fn plan<Src: RecipeSrc>(
+    recipe: &Recipe<Src>,
+    params: &DecoctionParams,
+) -> Result<Vec<Decoction>, DecoctionError> {
+    let grain = recipe.grain_mass();
+    let mut water = 0.0;
+    let mut temp = recipe.mash.grain_temp;
+    let mut decoctions = Vec::new();
+    for step in recipe.mash_steps() {
+        if step.type_ == Type::Decoction {
+            if water <= 0.0 {
+                return Err(DecoctionError::NoWater(step.name.clone()));
+            }
+            let mash = Mash { water, grain };
+            let ratio = params.thick_ratio.min(water / grain);
+            // Heat capacity per liter of thick mash
+            let thick = Mash::thick(1.0, ratio).heat_capacity();
+            let pull = mash.heat_capacity() * (step.step_temp - temp)
+                / (thick * (params.boil_temp - temp));
+            let pull = (pull / PULL_RESOLUTION).round() * PULL_RESOLUTION;
+            let decoction = Mash::thick(pull, ratio);
+            // The thick mash is thicker than the main mash, so a pull within the mash volume
+            // can still take more grain than there is
+            if !(0.0..=mash.volume()).contains(&pull)
+                || decoction.grain > grain
+                || decoction.water > water
+            {
+                return Err(DecoctionError::Unreachable {
+                    step: step.name.clone(),
+                    temp: step.step_temp,
+                });
+            }
+            decoctions.push(Decoction {
+                step: step.name.clone(),
+                pull,
+                grain: decoction.grain,
+                water: decoction.water,
+                schedule: vec![
+                    Rest {
+                        name: "Saccharification",
+                        temp: params.rest_temp,
+                        time: params.rest_time,
+                    },
+                    Rest {
+                        name: "Boil",
+                        temp: params.boil_temp,
+                        time: params.boil_time,
+                    },
+                ],
+                start_temp: temp,
+                mash_temp: returned_temp(
+                    mash.heat_capacity(),
+                    decoction.heat_capacity(),
+                    temp,
+                    params.boil_temp,
+                ),
+            });
+        }
+        water += step.infuse_amount.unwrap_or(0.0);
+        temp = step.end_temp.unwrap_or(step.step_temp);
+    }
+    Ok(decoctions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn thick_mash() {
+        let thick = Mash::thick(2.67, 2.0);
+        assert!((thick.grain - 1.0).abs() < 1e-6);
+        assert!((thick.water - 2.0).abs() < 1e-6);
+        // Returning a third of the heat capacity at 100 °C to a 52 °C mash
+        assert_eq!(returned_temp(3.0, 1.0, 52.0, 100.0), 68.0);
+    }
+}
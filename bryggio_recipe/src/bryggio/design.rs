@@ -0,0 +1,244 @@
+//! # Recipe design
+//!
+//! Solves the ingredient amounts of a recipe for target gravity, bitterness and color.
+//!
+//! A design gives the grist as percentages of the fermentable mass and the bitterness
+//! as shares of the IBU per hop addition, the way recipes are written in books.
+//! The fermentable amounts are solved from the gravity points, and the hop amounts from
+//! the recipe's IBU method with the new gravity.
+//!
+//! With a color target, the shares of the color malts (from 20 °L) are scaled
+//! against the rest of the grist to hit both the gravity and the color.
+use crate::bryggio::bitterness::Model;
+use crate::bryggio::quantity::Mass;
+use crate::bryggio::recipe::{Recipe, RecipeSrc};
+use crate::bryggio::Style;
+use brew_calculator::units::*;
+use thiserror::Error;
+
+/// Fermentables at least this dark, in °L, are used to adjust the color
+const COLOR_MALT: f32 = 20.0;
+/// Relative IBU error accepted when solving hop amounts
+const IBU_TOLERANCE: f32 = 1e-4;
+const MAX_ITERATIONS: usize = 50;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Targets {
+    pub og: SpecificGravity,
+    pub ibu: Ibu,
+    /// Color is left to follow from the grist if `None`
+    pub color: Option<SRMColor>,
+}
+
+impl Targets {
+    /// Midpoints of the style's ranges
+    pub fn from_style(style: &Style) -> Self {
+        Self {
+            og: (style.og_min + style.og_max) / 2.0,
+            ibu: (style.ibu_min + style.ibu_max) / 2.0,
+            color: Some((style.color_min + style.color_max) / 2.0),
+        }
+    }
+}
+
+/// Relative amounts of the ingredients, in recipe order
+#[derive(Clone, Debug, PartialEq)]
+pub struct Design {
+    /// Share of the grist mass of each fermentable
+    pub grist: Vec<Percent>,
+    /// Share of the total IBU of each hop addition
+    ///
+    /// Additions with a share of 0, like aroma and dry hops, keep their amount.
+    pub ibu: Vec<Percent>,
+}
+
+#[derive(Clone, Debug, PartialEq, Error)]
+pub enum DesignError {
+    #[error("Design has {shares} shares for {ingredients} ingredients")]
+    ShareCount { shares: usize, ingredients: usize },
+    #[error("Grist can not reach the original gravity")]
+    NoExtract,
+    #[error("Color {0} SRM can not be reached with the grist")]
+    UnreachableColor(SRMColor),
+    #[error("Hop '{0}' does not add bitterness")]
+    NoBitterness(String),
+    #[error("Amount of hop '{0}' did not converge")]
+    NotConverged(String),
+}
+
+impl Design {
+    /// Grist and IBU shares of an existing recipe
+    pub fn from_recipe<Src: RecipeSrc>(recipe: &Recipe<Src>) -> Self {
+        let grist: Kilograms = recipe.fermentables().map(|f| f.amount).sum();
+        let ibu = recipe.ibu();
+        Self {
+            grist: recipe
+                .fermentables()
+                .map(|fermentable| 100.0 * fermentable.amount / grist)
+                .collect(),
+            ibu: recipe
+                .hops()
+                .map(|hop| {
+                    let hop_ibu = recipe.hop_ibu(hop, &Model::Boil(recipe.ibu_method()));
+                    if ibu > 0.0 {
+                        100.0 * hop_ibu / ibu
+                    } else {
+                        0.0
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    /// Sets the amounts of the fermentables and hops in `recipe` to hit `targets`
+    ///
+    /// The grist shares don't need to sum to 100, they are normalized.
+    /// On error `recipe` is left unchanged.
+    pub fn apply<Src: RecipeSrc>(
+        &self,
+        recipe: &mut Recipe<Src>,
+        targets: &Targets,
+    ) -> Result<(), DesignError> {
+        check_count(self.grist.len(), recipe.fermentables.len())?;
+        check_count(self.ibu.len(), recipe.hops.len())?;
+        // The hops are solved with the solved grist, so both are solved in the recipe
+        // and the original amounts put back if either fails
+        let original = (recipe.fermentables.clone(), recipe.hops.clone(), recipe.og);
+        let solved = self.solve_grist(recipe, targets).and_then(|()| {
+            recipe.og = Some(targets.og);
+            self.solve_hops(recipe, targets.ibu)
+        });
+        if solved.is_err() {
+            (recipe.fermentables, recipe.hops, recipe.og) = original;
+        }
+        solved
+    }
+
+    fn solve_grist<Src: RecipeSrc>(
+        &self,
+        recipe: &mut Recipe<Src>,
+        targets: &Targets,
+    ) -> Result<(), DesignError> {
+        let total: Percent = self.grist.iter().sum();
+        // Points and color units per kg of grist, split in base and color malts
+        let (mut points, mut mcu) = ([0.0; 2], [0.0; 2]);
+        let mut shares = [0.0; 2];
+        for (fermentable, share) in recipe.fermentables.iter().zip(&self.grist) {
+            let share = share / total;
+            let group = usize::from(targets.color.is_some() && fermentable.color >= COLOR_MALT);
+            let mut kilogram = fermentable.clone();
+            kilogram.amount = 1.0;
+            points[group] += share * recipe.extract_points(&kilogram);
+            mcu[group] += share * fermentable.color * Mass::from_kilograms(1.0).pounds();
+            shares[group] += share;
+        }
+        let target_points = (targets.og - 1.0) * 1000.0 * recipe.batch_size;
+        // Grist mass the shares of the base and color malts are taken of
+        let grist = match targets.color {
+            Some(color) if shares[1] > 0.0 && shares[0] > 0.0 => {
+                let target_mcu =
+                    (color / 1.4922).powf(1.0 / 0.6859) * recipe.batch_volume().gallons();
+                // Solve points · grist = target_points and mcu · grist = target_mcu
+                let determinant = points[0] * mcu[1] - points[1] * mcu[0];
+                let base = (target_points * mcu[1] - points[1] * target_mcu) / determinant;
+                let color_malts = (points[0] * target_mcu - target_points * mcu[0]) / determinant;
+                if !(base > 0.0 && color_malts >= 0.0) {
+                    return Err(DesignError::UnreachableColor(color));
+                }
+                [base, color_malts]
+            }
+            _ => {
+                let grist = target_points / (points[0] + points[1]);
+                if !grist.is_finite() || grist <= 0.0 {
+                    return Err(DesignError::NoExtract);
+                }
+                [grist, grist]
+            }
+        };
+        for (fermentable, share) in recipe.fermentables.iter_mut().zip(&self.grist) {
+            let group = usize::from(targets.color.is_some() && fermentable.color >= COLOR_MALT);
+            fermentable.amount = grist[group] * share / total;
+        }
+        Ok(())
+    }
+
+    /// Hop amounts are refined until the IBU match,
+    /// since some IBU methods are not proportional to the amount
+    fn solve_hops<Src: RecipeSrc>(
+        &self,
+        recipe: &mut Recipe<Src>,
+        target: Ibu,
+    ) -> Result<(), DesignError> {
+        let model = Model::Boil(recipe.ibu_method());
+        for idx in 0..recipe.hops.len() {
+            let share = self.ibu[idx];
+            if share <= 0.0 {
+                continue;
+            }
+            let ibu = target * share / 100.0;
+            let mut hop = recipe.hops[idx].clone();
+            hop.amount = 0.001;
+            let mut converged = false;
+            for _ in 0..MAX_ITERATIONS {
+                let actual = recipe.hop_ibu(&hop, &model);
+                if actual <= 0.0 {
+                    return Err(DesignError::NoBitterness(hop.name));
+                }
+                if (actual - ibu).abs() <= IBU_TOLERANCE * ibu {
+                    converged = true;
+                    break;
+                }
+                hop.amount *= ibu / actual;
+            }
+            if !converged {
+                return Err(DesignError::NotConverged(hop.name));
+            }
+            recipe.hops[idx] = hop;
+        }
+        Ok(())
+    }
+}
+
+fn check_count(shares: usize, ingredients: usize) -> Result<(), DesignError> {
+    if shares == ingredients {
+        Ok(())
+    } else {
+        Err(DesignError::ShareCount {
+            shares,
+            ingredients,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn style_midpoints() {
+        let style: Style = serde_xml_rs::from_str(
+            "<STYLE>
+                <NAME>Dry Stout</NAME>
+                <CATEGORY>Stout</CATEGORY>
+                <VERSION>1</VERSION>
+                <CATEGORY_NUMBER>16</CATEGORY_NUMBER>
+                <STYLE_LETTER>A</STYLE_LETTER>
+                <STYLE_GUIDE>BJCP</STYLE_GUIDE>
+                <TYPE>Ale</TYPE>
+                <OG_MIN>1.035</OG_MIN>
+                <OG_MAX>1.050</OG_MAX>
+                <FG_MIN>1.007</FG_MIN>
+                <FG_MAX>1.011</FG_MAX>
+                <IBU_MIN>30.0</IBU_MIN>
+                <IBU_MAX>50.0</IBU_MAX>
+                <COLOR_MIN>35.0</COLOR_MIN>
+                <COLOR_MAX>200.0</COLOR_MAX>
+            </STYLE>",
+        )
+        .unwrap();
+        let targets = Targets::from_style(&style);
+        assert!((targets.og - 1.0425).abs() < 1e-4);
+        assert_eq!(targets.ibu, 40.0);
+        assert_eq!(targets.color, Some(117.5));
+    }
+}
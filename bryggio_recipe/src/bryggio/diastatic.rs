@@ -0,0 +1,141 @@
+//! # Diastatic power
+//!
+//! Enzymatic power of the grist, and whether the mash can convert its own starch.
+//!
+//! BeerXML gives the diastatic power of each fermentable in degrees Lintner.
+//! The grist's diastatic power is the mean weighted by amount over the mashed fermentables.
+//! A grist of at least 30 °Lintner is considered able to self-convert.
+//!
+//! In extract recipes the grains are steeped rather than mashed.
+//! Fermentables that must be mashed release unconverted starch when steeped.
+use crate::bryggio::recipe::{Recipe, RecipeSrc};
+use crate::bryggio::{Fermentable, Type};
+use beerxml::fermentable;
+use brew_calculator::units::*;
+use serde::Serialize;
+
+/// Smallest diastatic power of a grist that converts its own starch, °Lintner
+pub const SELF_CONVERSION: f32 = 30.0;
+
+/// Degrees Windisch-Kolbach from degrees Lintner
+pub fn windisch_kolbach(lintner: f32) -> f32 {
+    3.5 * lintner - 16.0
+}
+
+/// Degrees Lintner from degrees Windisch-Kolbach
+pub fn lintner(windisch_kolbach: f32) -> f32 {
+    (windisch_kolbach + 16.0) / 3.5
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct DiastaticPower {
+    /// Weighted mean of the grist, °Lintner
+    pub lintner: f32,
+    /// Grains without a diastatic power, left out of the mean
+    pub unknown: Vec<String>,
+}
+
+impl DiastaticPower {
+    /// Weighted mean of the grist, °WK
+    pub fn windisch_kolbach(&self) -> f32 {
+        windisch_kolbach(self.lintner)
+    }
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub enum Problem {
+    /// The grist's diastatic power is too low to convert its starch
+    NoSelfConversion { lintner: f32 },
+    /// A fermentable that must be mashed is steeped in an extract recipe
+    SteepedStarch { name: String },
+}
+
+/// Diastatic power of the grist and the conversion problems of a recipe
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct Conversion {
+    /// `None` for extract recipes, or if no mashed fermentable has a diastatic power
+    pub power: Option<DiastaticPower>,
+    pub problems: Vec<Problem>,
+}
+
+impl Conversion {
+    pub fn new<Src: RecipeSrc>(recipe: &Recipe<Src>) -> Self {
+        if recipe.type_ == Type::Extract {
+            let problems = recipe
+                .fermentables()
+                .filter(|fermentable| fermentable.type_.mashed() && must_be_mashed(fermentable))
+                .map(|fermentable| Problem::SteepedStarch {
+                    name: fermentable.name.clone(),
+                })
+                .collect();
+            return Self {
+                power: None,
+                problems,
+            };
+        }
+        let power = grist_power(recipe.fermentables());
+        let problems = power
+            .iter()
+            .filter(|power| power.lintner < SELF_CONVERSION)
+            .map(|power| Problem::NoSelfConversion {
+                lintner: power.lintner,
+            })
+            .collect();
+        Self { power, problems }
+    }
+
+    pub fn self_converts(&self) -> bool {
+        self.power
+            .as_ref()
+            .is_some_and(|power| power.lintner >= SELF_CONVERSION)
+    }
+}
+
+/// Diastatic power of the mashed fermentables, weighted by amount
+///
+/// Adjuncts without a diastatic power are counted as 0 °Lintner,
+/// grains without one are left out.
+pub fn grist_power<'a>(
+    fermentables: impl Iterator<Item = &'a Fermentable>,
+) -> Option<DiastaticPower> {
+    let mut unknown = Vec::new();
+    let mut weight: Kilograms = 0.0;
+    let mut power = 0.0;
+    for fermentable in fermentables.filter(|fermentable| fermentable.type_.mashed()) {
+        let diastatic_power = match (fermentable.diastatic_power, fermentable.type_) {
+            (Some(diastatic_power), _) => diastatic_power,
+            (None, fermentable::Type::Adjunct) => 0.0,
+            (None, _) => {
+                unknown.push(fermentable.name.clone());
+                continue;
+            }
+        };
+        weight += fermentable.amount;
+        power += diastatic_power * fermentable.amount;
+    }
+    if weight <= 0.0 {
+        return None;
+    }
+    Some(DiastaticPower {
+        lintner: power / weight,
+        unknown,
+    })
+}
+
+/// Adjuncts must be mashed unless marked otherwise, grains only if marked
+fn must_be_mashed(fermentable: &Fermentable) -> bool {
+    fermentable
+        .recommend_mash
+        .unwrap_or(fermentable.type_ == fermentable::Type::Adjunct)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lintner_and_windisch_kolbach() {
+        assert_eq!(windisch_kolbach(30.0), 89.0);
+        assert!((lintner(windisch_kolbach(140.0)) - 140.0).abs() < 1e-3);
+    }
+}
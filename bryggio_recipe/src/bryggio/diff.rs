@@ -0,0 +1,322 @@
+//! # Recipe diff
+//!
+//! Structural comparison of two versions of a recipe.
+//!
+//! Ingredients are matched by kind, name and use.
+//! Several additions with the same name and use, like two boil additions of the same hop,
+//! are matched in recipe order.
+use crate::bryggio::ingredient::Kind;
+use crate::bryggio::quantity::{Mass, Quantity, UnitSystem};
+use crate::bryggio::recipe::{Recipe, RecipeSrc};
+use brew_calculator::units::*;
+use serde::Serialize;
+use std::fmt;
+
+/// Smallest difference considered a change
+const TOLERANCE: f32 = 1e-6;
+
+/// Value before and after the change
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+pub struct Delta {
+    pub from: f32,
+    pub to: f32,
+}
+
+impl Delta {
+    pub fn new(from: f32, to: f32) -> Self {
+        Self { from, to }
+    }
+
+    pub fn change(self) -> f32 {
+        self.to - self.from
+    }
+
+    pub fn is_changed(self) -> bool {
+        self.change().abs() > TOLERANCE
+    }
+}
+
+/// Amount and time of an ingredient addition
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+pub struct Addition {
+    /// kg for fermentables and hops, kg or l for yeasts and miscs
+    pub amount: f32,
+    pub time: Option<Minutes>,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Change {
+    Added(Addition),
+    Removed(Addition),
+    Changed {
+        amount: Option<Delta>,
+        time: Option<Delta>,
+    },
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct IngredientChange {
+    pub kind: Kind,
+    pub name: String,
+    /// Hop or misc use
+    #[serde(rename = "use")]
+    pub use_: Option<String>,
+    pub change: Change,
+}
+
+/// Differences between two recipes
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct RecipeDiff {
+    pub from: String,
+    pub to: String,
+    pub batch_size: Delta,
+    pub ingredients: Vec<IngredientChange>,
+    /// Estimated from the ingredients, a specified OG is ignored
+    pub og: Delta,
+    pub ibu: Delta,
+    /// Color in SRM
+    pub color: Delta,
+    /// Estimated from the ingredients, specified gravities are ignored
+    pub abv: Delta,
+}
+
+/// Ingredient reduced to what is compared
+struct Entry {
+    kind: Kind,
+    name: String,
+    use_: Option<String>,
+    addition: Addition,
+}
+
+impl RecipeDiff {
+    pub fn new<A: RecipeSrc, B: RecipeSrc>(from: &Recipe<A>, to: &Recipe<B>) -> Self {
+        let mut ingredients = Vec::new();
+        let (from_entries, to_entries) = (entries(from), entries(to));
+        let mut matched = vec![false; to_entries.len()];
+        for old in &from_entries {
+            let new = to_entries.iter().enumerate().find(|(idx, new)| {
+                !matched[*idx]
+                    && new.kind == old.kind
+                    && new.name == old.name
+                    && new.use_ == old.use_
+            });
+            match new {
+                Some((idx, new)) => {
+                    matched[idx] = true;
+                    if let Some(change) = changed(&old.addition, &new.addition) {
+                        ingredients.push(old.change(change));
+                    }
+                }
+                None => ingredients.push(old.change(Change::Removed(old.addition))),
+            }
+        }
+        for (new, _) in to_entries
+            .iter()
+            .zip(matched)
+            .filter(|(_, matched)| !matched)
+        {
+            ingredients.push(new.change(Change::Added(new.addition)));
+        }
+        Self {
+            from: from.name.clone(),
+            to: to.name.clone(),
+            batch_size: Delta::new(from.batch_size, to.batch_size),
+            ingredients,
+            og: Delta::new(from.estimated_og(), to.estimated_og()),
+            ibu: Delta::new(from.ibu(), to.ibu()),
+            color: Delta::new(from.estimated_color(), to.estimated_color()),
+            abv: Delta::new(from.estimated_abv(), to.estimated_abv()),
+        }
+    }
+
+    /// True if the recipes have the same batch size and ingredients
+    pub fn is_empty(&self) -> bool {
+        self.ingredients.is_empty() && !self.batch_size.is_changed()
+    }
+}
+
+impl Entry {
+    fn change(&self, change: Change) -> IngredientChange {
+        IngredientChange {
+            kind: self.kind,
+            name: self.name.clone(),
+            use_: self.use_.clone(),
+            change,
+        }
+    }
+}
+
+fn entries<Src: RecipeSrc>(recipe: &Recipe<Src>) -> Vec<Entry> {
+    let fermentables = recipe.fermentables().map(|fermentable| Entry {
+        kind: Kind::Fermentable,
+        name: fermentable.name.clone(),
+        use_: None,
+        addition: Addition {
+            amount: fermentable.amount,
+            time: None,
+        },
+    });
+    let hops = recipe.hops().map(|hop| Entry {
+        kind: Kind::Hop,
+        name: hop.name.clone(),
+        use_: Some(hop.use_.to_string()),
+        addition: Addition {
+            amount: hop.amount,
+            time: Some(hop.time),
+        },
+    });
+    let yeasts = recipe.yeasts().map(|yeast| Entry {
+        kind: Kind::Yeast,
+        name: yeast.name.clone(),
+        use_: None,
+        addition: Addition {
+            amount: yeast.amount,
+            time: None,
+        },
+    });
+    let miscs = recipe.miscs().map(|misc| Entry {
+        kind: Kind::Misc,
+        name: misc.name.clone(),
+        use_: Some(misc.use_.to_string()),
+        addition: Addition {
+            amount: misc.amount,
+            time: Some(misc.time),
+        },
+    });
+    fermentables
+        .chain(hops)
+        .chain(yeasts)
+        .chain(miscs)
+        .collect()
+}
+
+fn changed(from: &Addition, to: &Addition) -> Option<Change> {
+    let amount = Some(Delta::new(from.amount, to.amount)).filter(|delta| delta.is_changed());
+    let time = match (from.time, to.time) {
+        (Some(from), Some(to)) => Some(Delta::new(from, to)).filter(|delta| delta.is_changed()),
+        _ => None,
+    };
+    if amount.is_none() && time.is_none() {
+        None
+    } else {
+        Some(Change::Changed { amount, time })
+    }
+}
+
+impl fmt::Display for RecipeDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{} -> {}", self.from, self.to)?;
+        if self.batch_size.is_changed() {
+            writeln!(
+                f,
+                "  batch size: {:.1} -> {:.1} l",
+                self.batch_size.from, self.batch_size.to
+            )?;
+        }
+        for ingredient in &self.ingredients {
+            writeln!(f, "  {}", ingredient)?;
+        }
+        writeln!(
+            f,
+            "OG:    {:.3} -> {:.3} ({:+.3})",
+            self.og.from,
+            self.og.to,
+            self.og.change()
+        )?;
+        writeln!(
+            f,
+            "IBU:   {:.1} -> {:.1} ({:+.1})",
+            self.ibu.from,
+            self.ibu.to,
+            self.ibu.change()
+        )?;
+        writeln!(
+            f,
+            "Color: {:.1} -> {:.1} SRM ({:+.1})",
+            self.color.from,
+            self.color.to,
+            self.color.change()
+        )?;
+        write!(
+            f,
+            "ABV:   {:.1} -> {:.1} % ({:+.1})",
+            self.abv.from,
+            self.abv.to,
+            self.abv.change()
+        )
+    }
+}
+
+impl fmt::Display for IngredientChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let sign = match self.change {
+            Change::Added(_) => '+',
+            Change::Removed(_) => '-',
+            Change::Changed { .. } => '~',
+        };
+        write!(f, "{} {:?} {}", sign, self.kind, self.name)?;
+        if let Some(use_) = &self.use_ {
+            write!(f, " ({})", use_)?;
+        }
+        match self.change {
+            Change::Added(addition) | Change::Removed(addition) => {
+                write!(f, ": {}", self.amount(addition.amount))?;
+                if let Some(time) = addition.time {
+                    write!(f, ", {} min", time)?;
+                }
+                Ok(())
+            }
+            Change::Changed { amount, time } => {
+                let mut changes = Vec::new();
+                if let Some(amount) = amount {
+                    changes.push(format!(
+                        "amount {} -> {}",
+                        self.amount(amount.from),
+                        self.amount(amount.to)
+                    ));
+                }
+                if let Some(time) = time {
+                    changes.push(format!("time {} -> {} min", time.from, time.to));
+                }
+                write!(f, ": {}", changes.join(", "))
+            }
+        }
+    }
+}
+
+impl IngredientChange {
+    fn amount(&self, amount: f32) -> String {
+        match self.kind {
+            Kind::Fermentable | Kind::Hop => Mass::from_kilograms(amount)
+                .display(UnitSystem::Metric)
+                .to_string(),
+            Kind::Yeast | Kind::Misc => format!("{}", amount),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unchanged_addition() {
+        let addition = Addition {
+            amount: 0.025,
+            time: Some(60.0),
+        };
+        assert_eq!(changed(&addition, &addition), None);
+        let later = Addition {
+            time: Some(15.0),
+            ..addition
+        };
+        assert_eq!(
+            changed(&addition, &later),
+            Some(Change::Changed {
+                amount: None,
+                time: Some(Delta::new(60.0, 15.0))
+            })
+        );
+    }
+}
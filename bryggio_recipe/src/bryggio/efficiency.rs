@@ -0,0 +1,148 @@
+//! # Efficiency
+//!
+//! Breakdown of the brewhouse efficiency into the steps where extract is lost.
+//!
+//! - Conversion: share of the grist's potential extract dissolved in the mash
+//! - Mash: share of the potential extract collected in the boil kettle
+//! - Lauter: share of the dissolved extract collected in the boil kettle
+//! - Brewhouse: share of the potential extract in the fermenter
+//!
+//! The efficiencies are related by $mash = conversion \cdot lauter$.
+use crate::bryggio::quantity::Gravity;
+use crate::bryggio::recipe::{Recipe, RecipeSrc};
+use crate::bryggio::sparge::SpargeParams;
+use crate::bryggio::Fermentable;
+use brew_calculator::units::*;
+use serde::{Deserialize, Serialize};
+
+/// Gravity and volume readings from the brew day
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+pub struct Readings {
+    /// Strike water, taken from the recipe's lauter plan if not measured
+    pub mash_water: Option<Liters>,
+    /// Gravity of the first wort running off the mash
+    pub first_wort_gravity: Option<SpecificGravity>,
+    pub pre_boil_gravity: Option<SpecificGravity>,
+    pub pre_boil_volume: Option<Liters>,
+    pub og: Option<SpecificGravity>,
+    /// Volume into the fermenter
+    pub batch_volume: Option<Liters>,
+}
+
+/// Efficiencies in percent
+///
+/// An efficiency is `None` when the needed readings are missing,
+/// or when the recipe has no mashed fermentables, as in an extract recipe.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Default)]
+pub struct EfficiencyBreakdown {
+    pub conversion: Option<Percent>,
+    pub mash: Option<Percent>,
+    pub lauter: Option<Percent>,
+    pub brewhouse: Option<Percent>,
+}
+
+impl EfficiencyBreakdown {
+    pub fn new<Src: RecipeSrc>(recipe: &Recipe<Src>, readings: &Readings) -> Self {
+        let mashed: Vec<&Fermentable> = recipe
+            .fermentables()
+            .filter(|fermentable| fermentable.type_.mashed())
+            .collect();
+        let potential: Kilograms = mashed.iter().copied().map(potential_extract).sum();
+        if potential <= 0.0 {
+            return Self::default();
+        }
+        // Sugars and extracts are dissolved in the kettle
+        let unmashed: Kilograms = recipe
+            .fermentables()
+            .filter(|fermentable| {
+                !fermentable.type_.mashed() && !fermentable.add_after_boil.unwrap_or(false)
+            })
+            .map(potential_extract)
+            .sum();
+
+        // Mashed in the full volume on a brew-in-a-bag system, and with the water
+        // below the malt pipe on an all-in-one system
+        let mash_water = readings.mash_water.or_else(|| {
+            recipe
+                .lauter_plan(&SpargeParams::default())
+                .ok()
+                .map(|plan| plan.mash_water)
+        });
+        let conversion = readings
+            .first_wort_gravity
+            .zip(mash_water)
+            .map(|(gravity, water)| {
+                let grain_water: Kilograms = mashed.iter().copied().map(moisture).sum();
+                let plato = Gravity::from_specific_gravity(gravity).plato();
+                // Extract dissolved in the water of the mash
+                let dissolved = (water + grain_water) * plato / (100.0 - plato);
+                100.0 * dissolved / potential
+            });
+        let mash = readings
+            .pre_boil_gravity
+            .zip(readings.pre_boil_volume)
+            .map(|(gravity, volume)| 100.0 * wort_extract(gravity, volume) / potential);
+        let lauter = mash
+            .zip(conversion)
+            .map(|(mash, conversion)| 100.0 * mash / conversion);
+        let brewhouse = readings.og.map(|og| {
+            let volume = readings.batch_volume.unwrap_or(recipe.batch_size);
+            100.0 * (wort_extract(og, volume) - unmashed) / potential
+        });
+        Self {
+            conversion,
+            mash,
+            lauter,
+            brewhouse,
+        }
+    }
+}
+
+/// Extract a fermentable yields at full conversion, in kg
+///
+/// Grains and adjuncts yield their dry, fine grind yield, reduced by the coarse/fine difference
+/// and the moisture content, when given.
+/// Sugars and extracts yield their raw yield by weight.
+pub fn potential_extract(fermentable: &Fermentable) -> Kilograms {
+    if fermentable.type_.mashed() {
+        let yield_ = fermentable.yield_ - fermentable.coarse_fine_diff.unwrap_or(0.0);
+        let dry = 1.0 - fermentable.moisture.unwrap_or(0.0) / 100.0;
+        fermentable.amount * dry * yield_ / 100.0
+    } else {
+        fermentable.amount * fermentable.yield_ / 100.0
+    }
+}
+
+/// Water in the grain, in kg
+fn moisture(fermentable: &Fermentable) -> Kilograms {
+    fermentable.amount * fermentable.moisture.unwrap_or(0.0) / 100.0
+}
+
+/// Extract dissolved in the wort, in kg
+///
+/// The wort weighs $V \cdot SG$ kg, of which $P$ % is extract.
+fn wort_extract(gravity: SpecificGravity, volume: Liters) -> Kilograms {
+    volume * gravity * Gravity::from_specific_gravity(gravity).plato() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use beerxml::fermentable::Type;
+
+    #[test]
+    fn potential_of_moist_grain() {
+        let mut malt = Fermentable::new("Pale Ale".into(), Type::Grain, 5.0, 80.0, 3.0);
+        assert!((potential_extract(&malt) - 4.0).abs() < 1e-5);
+        malt.coarse_fine_diff = Some(2.0);
+        malt.moisture = Some(4.0);
+        assert!((potential_extract(&malt) - 5.0 * 0.96 * 0.78).abs() < 1e-5);
+        assert!((moisture(&malt) - 0.2).abs() < 1e-5);
+    }
+
+    #[test]
+    fn wort_extract_from_gravity() {
+        // 1.048 is close to 12 °P
+        assert!((wort_extract(1.048, 20.0) - 20.0 * 1.048 * 0.119).abs() < 0.05);
+    }
+}
@@ -0,0 +1,234 @@
+//! # Enrichment
+//!
+//! Completes imported recipes with the optional ingredient data the exporting tool left out.
+//!
+//! Each ingredient is matched against a reference library, yeasts first by product id,
+//! then by name. Names are matched fuzzily, so that "Goldings, East Kent" matches
+//! "East Kent Goldings" and "Hallertauer Mittelfruh" matches "Hallertau Mittelfrueh".
+//! Every field filled in from the library is recorded for review.
+use crate::bryggio::ingredient::Kind;
+use crate::bryggio::library::{self, Library};
+use serde::Serialize;
+
+/// Smallest name similarity accepted as a match
+const MATCH_THRESHOLD: f32 = 0.75;
+
+/// Field filled in from the library
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct InferredField {
+    pub kind: Kind,
+    /// Name of the ingredient in the recipe
+    pub name: String,
+    /// Name of the library ingredient it was matched with
+    pub reference: String,
+    /// Similarity of the match, 1 for an exact name or product id match
+    pub similarity: f32,
+    /// BeerXML field name, in lower case
+    pub field: &'static str,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq, Default)]
+pub struct Enrichment {
+    pub inferred: Vec<InferredField>,
+    /// Ingredients without a match in the library
+    pub unmatched: Vec<(Kind, String)>,
+}
+
+/// Fills in missing optional fields of the ingredients in `recipe` from `library`
+pub fn enrich(recipe: &mut beerxml::Recipe, library: &Library) -> Enrichment {
+    let mut enrichment = Enrichment::default();
+    for hop in &mut recipe.hops.hop {
+        match best_match(&hop.name, library.hops.iter(), |hop| &hop.name) {
+            Some((reference, similarity)) => {
+                let fields = library::complete_hop(hop, reference);
+                enrichment.record(Kind::Hop, &hop.name, &reference.name, similarity, fields);
+            }
+            None => enrichment.unmatched.push((Kind::Hop, hop.name.clone())),
+        }
+    }
+    for fermentable in &mut recipe.fermentables.fermentable {
+        let candidates = library
+            .fermentables
+            .iter()
+            .filter(|reference| reference.type_ == fermentable.type_);
+        match best_match(&fermentable.name, candidates, |fermentable| {
+            &fermentable.name
+        }) {
+            Some((reference, similarity)) => {
+                let fields = library::complete_fermentable(fermentable, reference);
+                enrichment.record(
+                    Kind::Fermentable,
+                    &fermentable.name,
+                    &reference.name,
+                    similarity,
+                    fields,
+                );
+            }
+            None => enrichment
+                .unmatched
+                .push((Kind::Fermentable, fermentable.name.clone())),
+        }
+    }
+    for yeast in &mut recipe.yeasts.yeast {
+        let by_product_id = yeast.product_id.as_deref().and_then(|product_id| {
+            library
+                .yeasts
+                .iter()
+                .find(|reference| {
+                    reference.product_id.as_deref().map(normalize) == Some(normalize(product_id))
+                })
+                .map(|reference| (reference, 1.0))
+        });
+        match by_product_id
+            .or_else(|| best_match(&yeast.name, library.yeasts.iter(), |yeast| &yeast.name))
+        {
+            Some((reference, similarity)) => {
+                let fields = library::complete_yeast(yeast, reference);
+                enrichment.record(
+                    Kind::Yeast,
+                    &yeast.name,
+                    &reference.name,
+                    similarity,
+                    fields,
+                );
+            }
+            None => enrichment.unmatched.push((Kind::Yeast, yeast.name.clone())),
+        }
+    }
+    for misc in &mut recipe.miscs.misc {
+        match best_match(&misc.name, library.miscs.iter(), |misc| &misc.name) {
+            Some((reference, similarity)) => {
+                let fields = library::complete_misc(misc, reference);
+                enrichment.record(Kind::Misc, &misc.name, &reference.name, similarity, fields);
+            }
+            None => enrichment.unmatched.push((Kind::Misc, misc.name.clone())),
+        }
+    }
+    enrichment
+}
+
+impl Enrichment {
+    fn record(
+        &mut self,
+        kind: Kind,
+        name: &str,
+        reference: &str,
+        similarity: f32,
+        fields: Vec<&'static str>,
+    ) {
+        self.inferred
+            .extend(fields.into_iter().map(|field| InferredField {
+                kind,
+                name: name.to_string(),
+                reference: reference.to_string(),
+                similarity,
+                field,
+            }));
+    }
+}
+
+/// Candidate with the name most similar to `name`, if similar enough
+fn best_match<'a, T>(
+    name: &str,
+    candidates: impl Iterator<Item = &'a T>,
+    candidate_name: fn(&T) -> &str,
+) -> Option<(&'a T, f32)> {
+    candidates
+        .map(|candidate| (candidate, name_similarity(name, candidate_name(candidate))))
+        .filter(|(_, similarity)| *similarity >= MATCH_THRESHOLD)
+        .fold(
+            None,
+            |best: Option<(&T, f32)>, (candidate, similarity)| match best {
+                Some((_, best_similarity)) if best_similarity >= similarity => best,
+                _ => Some((candidate, similarity)),
+            },
+        )
+}
+
+/// Similarity of two names from 0 to 1, independent of word order, case and punctuation
+///
+/// Every word is paired with the most similar word of the other name.
+/// The similarity is the mean over the words of both names.
+pub fn name_similarity(a: &str, b: &str) -> f32 {
+    let (a, b) = (words(a), words(b));
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let coverage = |from: &[String], to: &[String]| {
+        from.iter()
+            .map(|word| {
+                to.iter()
+                    .map(|other| word_similarity(word, other))
+                    .fold(0.0, f32::max)
+            })
+            .sum::<f32>()
+    };
+    (coverage(&a, &b) + coverage(&b, &a)) / (a.len() + b.len()) as f32
+}
+
+/// Lower case alphanumeric words, with German umlauts spelled out
+fn words(name: &str) -> Vec<String> {
+    name.to_lowercase()
+        .replace('ä', "ae")
+        .replace('ö', "oe")
+        .replace('ü', "ue")
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+fn normalize(product_id: &str) -> String {
+    words(product_id).concat()
+}
+
+/// 1 minus the edit distance relative to the longer word
+fn word_similarity(a: &str, b: &str) -> f32 {
+    let length = a.chars().count().max(b.chars().count());
+    if length == 0 {
+        return 1.0;
+    }
+    1.0 - levenshtein(a, b) as f32 / length as f32
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b) in b.iter().enumerate() {
+            let substitution = previous[j] + if a == *b { 0 } else { 1 };
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edit_distance() {
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+        assert_eq!(levenshtein("", "ale"), 3);
+        assert_eq!(levenshtein("malt", "malt"), 0);
+    }
+
+    #[test]
+    fn similar_names() {
+        assert_eq!(
+            name_similarity("Goldings, East Kent", "East Kent Goldings"),
+            1.0
+        );
+        assert_eq!(
+            name_similarity("Hallertauer Mittelfrüh", "Hallertau Mittelfrueh"),
+            name_similarity("Hallertauer Mittelfrueh", "Hallertau Mittelfrueh")
+        );
+        assert!(name_similarity("Pale Malt (2 row) UK", "Pale Malt (2 Row)") > MATCH_THRESHOLD);
+        assert!(name_similarity("Pale Malt (2 row) UK", "Pale Ale Malt") < MATCH_THRESHOLD);
+        assert!(name_similarity("Cascade", "Chinook") < MATCH_THRESHOLD);
+        assert_eq!(normalize("WLP-001"), normalize("wlp001"));
+    }
+}
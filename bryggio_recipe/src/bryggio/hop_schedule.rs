@@ -0,0 +1,191 @@
+//! # Hop schedule
+//!
+//! Bittering additions that reach a target bitterness with the hops in stock.
+//!
+//! The hops already in the recipe are kept as fixed additions, typically for aroma.
+//! The remaining bitterness is made up with boil additions of the candidate hops,
+//! limited to the amounts in the inventory and using the alpha acids of each lot.
+//! Additions are chosen for the least hop mass or the lowest cost per IBU,
+//! which favours the longest allowed boil times.
+//!
+//! The IBU of an addition are taken as proportional to its amount,
+//! which is exact for Tinseth and Rager and close for Garetz.
+use crate::bryggio::bitterness::Model;
+use crate::bryggio::cost::PriceList;
+use crate::bryggio::ingredient::Kind;
+use crate::bryggio::inventory::Inventory;
+use crate::bryggio::recipe::{Recipe, RecipeSrc};
+use crate::bryggio::Hop;
+use beerxml::hop::Use;
+use brew_calculator::units::*;
+use thiserror::Error;
+
+/// Hop amount the IBU per kg are calculated from, to stay in the range of real additions
+const REFERENCE_AMOUNT: Kilograms = 0.01;
+/// Smallest addition worth weighing, kg
+const MIN_ADDITION: Kilograms = 1e-4;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Target {
+    Ibu(Ibu),
+    /// Bitterness to original gravity ratio
+    BuGu(f32),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Objective<'a> {
+    /// Least total hop mass
+    Usage,
+    /// Lowest price, hops without a valid price are not used
+    Cost(&'a PriceList),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Error)]
+pub enum ScheduleError {
+    #[error("Fixed additions already give {0:.1} IBU")]
+    TargetExceeded(Ibu),
+    #[error("Hops in stock are {0:.1} IBU short of the target")]
+    NotEnoughHops(Ibu),
+}
+
+/// Bittering additions chosen for a recipe
+#[derive(Clone, Debug, PartialEq)]
+pub struct Schedule {
+    /// Boil additions, in addition to the recipe's hops
+    pub additions: Vec<Hop>,
+    /// IBU of the recipe with the additions
+    pub ibu: Ibu,
+    /// Price of the additions when optimized for cost
+    pub cost: Option<f32>,
+}
+
+impl Schedule {
+    /// Adds the additions to the hops of `recipe`
+    pub fn apply<Src: RecipeSrc>(self, recipe: &mut Recipe<Src>) {
+        recipe.hops.extend(self.additions);
+    }
+}
+
+/// Hop lot at a boil time
+struct Choice {
+    hop: Hop,
+    lot: usize,
+    ibu_per_kg: f32,
+    /// Price per kg of the hop, if known
+    unit_price: Option<f32>,
+}
+
+/// Cheapest boil additions of `candidates` that bring `recipe` to `target`
+///
+/// Each hop lot in `inventory` matching a candidate by name can be boiled for any of `times`
+/// within the recipe's boil time. Lots without alpha acids use the candidate's.
+pub fn optimize<Src: RecipeSrc>(
+    recipe: &Recipe<Src>,
+    candidates: &[Hop],
+    inventory: &Inventory,
+    times: &[Minutes],
+    target: Target,
+    objective: Objective,
+) -> Result<Schedule, ScheduleError> {
+    let model = Model::Boil(recipe.ibu_method());
+    let target = match target {
+        Target::Ibu(ibu) => ibu,
+        Target::BuGu(bu_gu) => bu_gu * recipe.original_gravity().points(),
+    };
+    let fixed = recipe.ibu();
+    if fixed > target {
+        return Err(ScheduleError::TargetExceeded(fixed));
+    }
+    let mut choices = Vec::new();
+    for candidate in candidates {
+        let unit_price = match objective {
+            Objective::Usage => None,
+            Objective::Cost(prices) => match prices.price(Kind::Hop, &candidate.name) {
+                Some(price) if price.unit_price().is_finite() => Some(price.unit_price()),
+                _ => continue,
+            },
+        };
+        let lots = inventory.lots.iter().enumerate().filter(|(_, lot)| {
+            lot.kind == Kind::Hop && lot.name.eq_ignore_ascii_case(&candidate.name)
+        });
+        for (idx, lot) in lots {
+            for &time in times.iter().filter(|&&time| time <= recipe.boil_time()) {
+                let mut hop = candidate.clone();
+                hop.alpha = lot.alpha.unwrap_or(candidate.alpha);
+                hop.use_ = Use::Boil;
+                hop.time = time;
+                hop.amount = REFERENCE_AMOUNT;
+                let ibu_per_kg = recipe.hop_ibu(&hop, &model) / REFERENCE_AMOUNT;
+                if ibu_per_kg > 0.0 {
+                    choices.push(Choice {
+                        hop,
+                        lot: idx,
+                        ibu_per_kg,
+                        unit_price,
+                    });
+                }
+            }
+        }
+    }
+    let per_ibu = |choice: &Choice| choice.unit_price.unwrap_or(1.0) / choice.ibu_per_kg;
+    choices.sort_by(|a, b| per_ibu(a).total_cmp(&per_ibu(b)));
+
+    let mut remaining: Vec<Kilograms> = inventory.lots.iter().map(|lot| lot.amount).collect();
+    let mut missing = target - fixed;
+    let mut additions: Vec<Hop> = Vec::new();
+    for choice in choices {
+        if missing <= 0.0 {
+            break;
+        }
+        let amount = remaining[choice.lot].min(missing / choice.ibu_per_kg);
+        if amount < MIN_ADDITION {
+            continue;
+        }
+        remaining[choice.lot] -= amount;
+        missing -= amount * choice.ibu_per_kg;
+        match additions.iter_mut().find(|addition| {
+            addition.name == choice.hop.name
+                && addition.time == choice.hop.time
+                && addition.alpha == choice.hop.alpha
+        }) {
+            Some(addition) => addition.amount += amount,
+            None => {
+                let mut hop = choice.hop;
+                hop.amount = amount;
+                additions.push(hop);
+            }
+        }
+    }
+    if missing > 0.01 * target {
+        return Err(ScheduleError::NotEnoughHops(missing));
+    }
+    let ibu = fixed
+        + additions
+            .iter()
+            .map(|hop| recipe.hop_ibu(hop, &model))
+            .sum::<Ibu>();
+    let cost = match objective {
+        Objective::Usage => None,
+        Objective::Cost(prices) => Some(cost(&additions, prices)),
+    };
+    Ok(Schedule {
+        additions,
+        ibu,
+        cost,
+    })
+}
+
+/// Price of buying the total amount of each hop of `additions`, in whole packs when sold so
+fn cost(additions: &[Hop], prices: &PriceList) -> f32 {
+    let mut totals: Vec<(&str, Kilograms)> = Vec::new();
+    for hop in additions {
+        match totals.iter_mut().find(|(name, _)| *name == hop.name) {
+            Some((_, amount)) => *amount += hop.amount,
+            None => totals.push((&hop.name, hop.amount)),
+        }
+    }
+    totals
+        .into_iter()
+        .filter_map(|(name, amount)| Some(prices.price(Kind::Hop, name)?.cost(amount)))
+        .sum()
+}
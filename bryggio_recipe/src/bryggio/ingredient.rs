@@ -0,0 +1,14 @@
+//! # Ingredient
+//!
+//! Kinds of ingredients shared by the inventory, price list, library and recipe diff.
+use serde::{Deserialize, Serialize};
+
+/// Kind of ingredient
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Kind {
+    Fermentable,
+    Hop,
+    Yeast,
+    Misc,
+}
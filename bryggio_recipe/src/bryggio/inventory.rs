@@ -0,0 +1,259 @@
+//! # Inventory
+//!
+//! Stock of ingredients, checked against and deducted by recipes.
+//!
+//! Stock is kept per lot, in the units of BeerXML amounts:
+//! kg for fermentables and hops, and kg or l for yeasts and miscs.
+//! Ingredients are matched by kind and name, ignoring case.
+use crate::bryggio::ingredient::Kind;
+use crate::bryggio::quantity::{Mass, Volume};
+use crate::bryggio::recipe::{Recipe, RecipeSrc};
+use brew_calculator::units::*;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Smallest amount considered in stock
+const TOLERANCE: f32 = 1e-6;
+
+/// A lot of an ingredient in stock
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Lot {
+    pub kind: Kind,
+    pub name: String,
+    pub amount: f32,
+    /// Lot number
+    pub lot: Option<String>,
+    /// Date as `YYYY-MM-DD`
+    pub purchased: Option<String>,
+    /// Date as `YYYY-MM-DD`, for hops
+    pub harvested: Option<String>,
+    /// Alpha acids of this lot, for hops
+    pub alpha: Option<Percent>,
+}
+
+impl Lot {
+    pub fn new(kind: Kind, name: String, amount: f32) -> Self {
+        Self {
+            kind,
+            name,
+            amount,
+            lot: None,
+            purchased: None,
+            harvested: None,
+            alpha: None,
+        }
+    }
+
+    fn matches(&self, kind: Kind, name: &str) -> bool {
+        self.kind == kind && self.name.eq_ignore_ascii_case(name)
+    }
+
+    /// Oldest lots are used first, lots without dates last
+    fn age_key(&self) -> (bool, Option<&String>) {
+        let date = self.harvested.as_ref().or(self.purchased.as_ref());
+        (date.is_none(), date)
+    }
+}
+
+/// Ingredient missing from the inventory
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct Shortfall {
+    pub kind: Kind,
+    pub name: String,
+    pub required: f32,
+    pub available: f32,
+}
+
+impl Shortfall {
+    pub fn missing(&self) -> f32 {
+        self.required - self.available
+    }
+}
+
+#[derive(Clone, Debug, Error)]
+pub enum InventoryError {
+    #[error("Not enough in stock of {} ingredient(s)", .0.len())]
+    Shortfalls(Vec<Shortfall>),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct Inventory {
+    pub lots: Vec<Lot>,
+}
+
+impl Inventory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inventory from the BeerXML `INVENTORY` amounts of the recipe's ingredients
+    ///
+    /// Ingredients without a parsable inventory amount are left out.
+    pub fn from_recipe<Src: RecipeSrc>(recipe: &Recipe<Src>) -> Self {
+        let mut inventory = Self::new();
+        let ingredients = recipe
+            .fermentables()
+            .map(|f| (Kind::Fermentable, &f.name, &f.inventory))
+            .chain(recipe.hops().map(|h| (Kind::Hop, &h.name, &h.inventory)))
+            .chain(
+                recipe
+                    .yeasts()
+                    .map(|y| (Kind::Yeast, &y.name, &y.inventory)),
+            )
+            .chain(recipe.miscs().map(|m| (Kind::Misc, &m.name, &m.inventory)));
+        for (kind, name, stock) in ingredients {
+            let amount = stock.as_deref().and_then(parse_amount);
+            if let Some(amount) = amount {
+                if !inventory.lots.iter().any(|lot| lot.matches(kind, name)) {
+                    inventory.add(Lot::new(kind, name.clone(), amount));
+                }
+            }
+        }
+        inventory
+    }
+
+    pub fn add(&mut self, lot: Lot) {
+        self.lots.push(lot);
+    }
+
+    /// Total amount in stock over all lots
+    pub fn available(&self, kind: Kind, name: &str) -> f32 {
+        self.lots
+            .iter()
+            .filter(|lot| lot.matches(kind, name))
+            .map(|lot| lot.amount)
+            .sum()
+    }
+
+    /// Ingredients of `recipe` that are not in stock in the required amounts
+    pub fn shortfalls<Src: RecipeSrc>(&self, recipe: &Recipe<Src>) -> Vec<Shortfall> {
+        requirements(recipe)
+            .into_iter()
+            .filter_map(|(kind, name, required, _)| {
+                let available = self.available(kind, &name);
+                if required - available > TOLERANCE {
+                    Some(Shortfall {
+                        kind,
+                        name,
+                        required,
+                        available,
+                    })
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    pub fn is_brewable<Src: RecipeSrc>(&self, recipe: &Recipe<Src>) -> bool {
+        self.shortfalls(recipe).is_empty()
+    }
+
+    /// Deducts the ingredients of a brewed batch of `recipe`
+    ///
+    /// The oldest lots are used first and empty lots are removed.
+    /// Nothing is deducted if any ingredient is short.
+    pub fn deduct<Src: RecipeSrc>(&mut self, recipe: &Recipe<Src>) -> Result<(), InventoryError> {
+        let shortfalls = self.shortfalls(recipe);
+        if !shortfalls.is_empty() {
+            return Err(InventoryError::Shortfalls(shortfalls));
+        }
+        self.lots.sort_by(|a, b| a.age_key().cmp(&b.age_key()));
+        for (kind, name, mut required, _) in requirements(recipe) {
+            for lot in self.lots.iter_mut().filter(|lot| lot.matches(kind, &name)) {
+                let used = required.min(lot.amount);
+                lot.amount -= used;
+                required -= used;
+            }
+        }
+        self.lots.retain(|lot| lot.amount > TOLERANCE);
+        Ok(())
+    }
+}
+
+/// Total amount of every ingredient in the recipe
+///
+/// With whether the amount is in kg rather than l,
+/// `None` if the additions of an ingredient are in different units.
+pub(crate) fn requirements<Src: RecipeSrc>(
+    recipe: &Recipe<Src>,
+) -> Vec<(Kind, String, f32, Option<bool>)> {
+    let mut totals: Vec<(Kind, String, f32, Option<bool>)> = Vec::new();
+    let is_weight = |flag: Option<bool>| flag.unwrap_or(false);
+    let additions = recipe
+        .fermentables()
+        .map(|f| (Kind::Fermentable, &f.name, f.amount, true))
+        .chain(recipe.hops().map(|h| (Kind::Hop, &h.name, h.amount, true)))
+        .chain(recipe.yeasts().map(|y| {
+            let weight = is_weight(y.amount_is_weight);
+            (Kind::Yeast, &y.name, y.amount, weight)
+        }))
+        .chain(recipe.miscs().map(|m| {
+            let weight = is_weight(m.amount_is_weight);
+            (Kind::Misc, &m.name, m.amount, weight)
+        }));
+    for (kind, name, amount, weight) in additions {
+        match totals
+            .iter_mut()
+            .find(|(k, n, _, _)| *k == kind && n.eq_ignore_ascii_case(name))
+        {
+            Some(total) => {
+                total.2 += amount;
+                total.3 = total.3.filter(|total_weight| *total_weight == weight);
+            }
+            None => totals.push((kind, name.clone(), amount, Some(weight))),
+        }
+    }
+    totals
+}
+
+/// Amount in kg or l from a BeerXML display amount, like "2.5 lb" or "150 g"
+///
+/// Counts, like "1 pkg" or "2 items", are returned as is.
+pub fn parse_amount(display: &str) -> Option<f32> {
+    let display = display.trim();
+    let split = display
+        .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '-'))
+        .unwrap_or(display.len());
+    let value: f32 = display[..split].trim().parse().ok()?;
+    let amount = match display[split..].trim().to_ascii_lowercase().as_str() {
+        "mg" => Mass::from_grams(value / 1000.0).kilograms(),
+        "g" => Mass::from_grams(value).kilograms(),
+        "kg" => value,
+        "oz" => Mass::from_ounces(value).kilograms(),
+        "lb" | "lbs" => Mass::from_pounds(value).kilograms(),
+        "ml" => Volume::from_liters(value / 1000.0).liters(),
+        "l" => value,
+        "gal" => Volume::from_gallons(value).liters(),
+        "" | "pkg" | "items" | "item" | "each" => value,
+        _ => return None,
+    };
+    Some(amount)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_amounts() {
+        assert_eq!(parse_amount("150 g"), Some(0.15));
+        assert!((parse_amount("2.5 lb").unwrap() - 1.134).abs() < 1e-3);
+        assert_eq!(parse_amount("0.00 oz"), Some(0.0));
+        assert_eq!(parse_amount("1 pkg"), Some(1.0));
+        assert_eq!(parse_amount("a handful"), None);
+    }
+
+    #[test]
+    fn oldest_lot_first() {
+        let mut old = Lot::new(Kind::Hop, "Citra".into(), 0.05);
+        old.harvested = Some("2019-09-01".into());
+        let mut new = Lot::new(Kind::Hop, "Citra".into(), 0.1);
+        new.harvested = Some("2020-09-01".into());
+        let undated = Lot::new(Kind::Hop, "Citra".into(), 0.1);
+        let mut lots = [undated, new, old];
+        lots.sort_by(|a, b| a.age_key().cmp(&b.age_key()));
+        assert_eq!(lots[0].harvested.as_deref(), Some("2019-09-01"));
+        assert_eq!(lots[2].harvested, None);
+    }
+}
@@ -0,0 +1,370 @@
+//! # Library
+//!
+//! Collection of ingredients to look up and choose from,
+//! like the ingredient library files exported by brewing software.
+//!
+//! A reference library of common hops, malts, yeasts and miscs is bundled as BeerXML,
+//! see [`Library::bundled`].
+//! Reference ingredients can fill in the optional fields that are missing from
+//! the ingredients of imported recipes.
+use crate::bryggio::ingredient::Kind;
+use crate::bryggio::{Fermentable, Hop, Misc, Yeast};
+use beerxml::{fermentable::Fermentables, hop::Hops, misc::Miscs, yeast::Yeasts};
+
+const HOPS: &str = include_str!("../../data/library/hops.xml");
+const FERMENTABLES: &str = include_str!("../../data/library/fermentables.xml");
+const YEASTS: &str = include_str!("../../data/library/yeasts.xml");
+const MISCS: &str = include_str!("../../data/library/miscs.xml");
+
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct Library {
+    pub hops: Vec<Hop>,
+    pub fermentables: Vec<Fermentable>,
+    pub yeasts: Vec<Yeast>,
+    pub miscs: Vec<Misc>,
+}
+
+/// Ingredient found in a library
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Ingredient<'a> {
+    Hop(&'a Hop),
+    Fermentable(&'a Fermentable),
+    Yeast(&'a Yeast),
+    Misc(&'a Misc),
+}
+
+impl<'a> Ingredient<'a> {
+    pub fn kind(&self) -> Kind {
+        match self {
+            Ingredient::Hop(_) => Kind::Hop,
+            Ingredient::Fermentable(_) => Kind::Fermentable,
+            Ingredient::Yeast(_) => Kind::Yeast,
+            Ingredient::Misc(_) => Kind::Misc,
+        }
+    }
+
+    pub fn name(&self) -> &'a str {
+        match self {
+            Ingredient::Hop(hop) => &hop.name,
+            Ingredient::Fermentable(fermentable) => &fermentable.name,
+            Ingredient::Yeast(yeast) => &yeast.name,
+            Ingredient::Misc(misc) => &misc.name,
+        }
+    }
+
+    /// Name, origin, laboratory and product id, whichever the ingredient has
+    fn keywords(&self) -> Vec<&'a str> {
+        let mut keywords = vec![self.name()];
+        match self {
+            Ingredient::Hop(hop) => keywords.extend(hop.origin.as_deref()),
+            Ingredient::Fermentable(fermentable) => {
+                keywords.extend(fermentable.origin.as_deref());
+                keywords.extend(fermentable.supplier.as_deref());
+            }
+            Ingredient::Yeast(yeast) => {
+                keywords.extend(yeast.laboratory.as_deref());
+                keywords.extend(yeast.product_id.as_deref());
+            }
+            Ingredient::Misc(_) => {}
+        }
+        keywords
+    }
+}
+
+impl Library {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reference library of common ingredients shipped with the crate
+    pub fn bundled() -> Self {
+        let hops: Hops = serde_xml_rs::from_str(HOPS).expect("Invalid bundled hops");
+        let fermentables: Fermentables =
+            serde_xml_rs::from_str(FERMENTABLES).expect("Invalid bundled fermentables");
+        let yeasts: Yeasts = serde_xml_rs::from_str(YEASTS).expect("Invalid bundled yeasts");
+        let miscs: Miscs = serde_xml_rs::from_str(MISCS).expect("Invalid bundled miscs");
+        Self {
+            hops: hops.hop,
+            fermentables: fermentables.fermentable,
+            yeasts: yeasts.yeast,
+            miscs: miscs.misc,
+        }
+    }
+
+    /// Hop named `name`, ignoring case
+    pub fn hop(&self, name: &str) -> Option<&Hop> {
+        self.hops
+            .iter()
+            .find(|hop| hop.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Fermentable named `name`, ignoring case
+    pub fn fermentable(&self, name: &str) -> Option<&Fermentable> {
+        self.fermentables
+            .iter()
+            .find(|fermentable| fermentable.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Yeast named `name`, ignoring case
+    pub fn yeast(&self, name: &str) -> Option<&Yeast> {
+        self.yeasts
+            .iter()
+            .find(|yeast| yeast.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Misc named `name`, ignoring case
+    pub fn misc(&self, name: &str) -> Option<&Misc> {
+        self.miscs
+            .iter()
+            .find(|misc| misc.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn ingredients(&self) -> impl Iterator<Item = Ingredient<'_>> {
+        self.hops
+            .iter()
+            .map(Ingredient::Hop)
+            .chain(self.fermentables.iter().map(Ingredient::Fermentable))
+            .chain(self.yeasts.iter().map(Ingredient::Yeast))
+            .chain(self.miscs.iter().map(Ingredient::Misc))
+    }
+
+    /// Ingredients where every word of `query` is part of the name, origin, supplier,
+    /// laboratory or product id, ignoring case
+    pub fn search(&self, query: &str) -> Vec<Ingredient<'_>> {
+        let words: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+        self.ingredients()
+            .filter(|ingredient| {
+                let keywords = ingredient.keywords().join(" ").to_lowercase();
+                words.iter().all(|word| keywords.contains(word.as_str()))
+            })
+            .collect()
+    }
+
+    /// Fills in the missing optional fields of the ingredients in `recipe`
+    /// from the library ingredients with the same name
+    ///
+    /// Returns the number of filled fields.
+    pub fn complete(&self, recipe: &mut beerxml::Recipe) -> usize {
+        let mut filled = 0;
+        for hop in &mut recipe.hops.hop {
+            if let Some(reference) = self.hop(&hop.name) {
+                filled += complete_hop(hop, reference).len();
+            }
+        }
+        for fermentable in &mut recipe.fermentables.fermentable {
+            if let Some(reference) = self.fermentable(&fermentable.name) {
+                filled += complete_fermentable(fermentable, reference).len();
+            }
+        }
+        for yeast in &mut recipe.yeasts.yeast {
+            if let Some(reference) = self.yeast(&yeast.name) {
+                filled += complete_yeast(yeast, reference).len();
+            }
+        }
+        for misc in &mut recipe.miscs.misc {
+            if let Some(reference) = self.misc(&misc.name) {
+                filled += complete_misc(misc, reference).len();
+            }
+        }
+        filled
+    }
+}
+
+/// Fills in the missing optional fields of `hop` from `reference`
+///
+/// Returns the names of the filled fields.
+pub fn complete_hop(hop: &mut Hop, reference: &Hop) -> Vec<&'static str> {
+    let mut filled = Vec::new();
+    fill(&mut hop.form, &reference.form, "form", &mut filled);
+    fill(&mut hop.beta, &reference.beta, "beta", &mut filled);
+    fill(&mut hop.hsi, &reference.hsi, "hsi", &mut filled);
+    fill(&mut hop.origin, &reference.origin, "origin", &mut filled);
+    fill(
+        &mut hop.substitutes,
+        &reference.substitutes,
+        "substitutes",
+        &mut filled,
+    );
+    fill(
+        &mut hop.humulene,
+        &reference.humulene,
+        "humulene",
+        &mut filled,
+    );
+    fill(
+        &mut hop.caryophyllene,
+        &reference.caryophyllene,
+        "caryophyllene",
+        &mut filled,
+    );
+    fill(
+        &mut hop.cohumulone,
+        &reference.cohumulone,
+        "cohumulone",
+        &mut filled,
+    );
+    fill(&mut hop.myrcene, &reference.myrcene, "myrcene", &mut filled);
+    filled
+}
+
+/// Fills in the missing optional fields of `fermentable` from `reference`
+///
+/// Returns the names of the filled fields.
+pub fn complete_fermentable(
+    fermentable: &mut Fermentable,
+    reference: &Fermentable,
+) -> Vec<&'static str> {
+    let mut filled = Vec::new();
+    fill(
+        &mut fermentable.origin,
+        &reference.origin,
+        "origin",
+        &mut filled,
+    );
+    fill(
+        &mut fermentable.supplier,
+        &reference.supplier,
+        "supplier",
+        &mut filled,
+    );
+    fill(
+        &mut fermentable.coarse_fine_diff,
+        &reference.coarse_fine_diff,
+        "coarse_fine_diff",
+        &mut filled,
+    );
+    fill(
+        &mut fermentable.moisture,
+        &reference.moisture,
+        "moisture",
+        &mut filled,
+    );
+    fill(
+        &mut fermentable.diastatic_power,
+        &reference.diastatic_power,
+        "diastatic_power",
+        &mut filled,
+    );
+    fill(
+        &mut fermentable.protein,
+        &reference.protein,
+        "protein",
+        &mut filled,
+    );
+    fill(
+        &mut fermentable.max_in_batch,
+        &reference.max_in_batch,
+        "max_in_batch",
+        &mut filled,
+    );
+    fill(
+        &mut fermentable.recommend_mash,
+        &reference.recommend_mash,
+        "recommend_mash",
+        &mut filled,
+    );
+    filled
+}
+
+/// Fills in the missing optional fields of `yeast` from `reference`
+///
+/// Returns the names of the filled fields.
+pub fn complete_yeast(yeast: &mut Yeast, reference: &Yeast) -> Vec<&'static str> {
+    let mut filled = Vec::new();
+    fill(
+        &mut yeast.laboratory,
+        &reference.laboratory,
+        "laboratory",
+        &mut filled,
+    );
+    fill(
+        &mut yeast.product_id,
+        &reference.product_id,
+        "product_id",
+        &mut filled,
+    );
+    fill(
+        &mut yeast.min_temperature,
+        &reference.min_temperature,
+        "min_temperature",
+        &mut filled,
+    );
+    fill(
+        &mut yeast.max_temperature,
+        &reference.max_temperature,
+        "max_temperature",
+        &mut filled,
+    );
+    fill(
+        &mut yeast.flocculation,
+        &reference.flocculation,
+        "flocculation",
+        &mut filled,
+    );
+    fill(
+        &mut yeast.attenuation,
+        &reference.attenuation,
+        "attenuation",
+        &mut filled,
+    );
+    fill(
+        &mut yeast.best_for,
+        &reference.best_for,
+        "best_for",
+        &mut filled,
+    );
+    filled
+}
+
+/// Fills in the missing optional fields of `misc` from `reference`
+///
+/// Returns the names of the filled fields.
+pub fn complete_misc(misc: &mut Misc, reference: &Misc) -> Vec<&'static str> {
+    let mut filled = Vec::new();
+    fill(
+        &mut misc.use_for,
+        &reference.use_for,
+        "use_for",
+        &mut filled,
+    );
+    filled
+}
+
+fn fill<T: Clone>(
+    field: &mut Option<T>,
+    reference: &Option<T>,
+    name: &'static str,
+    filled: &mut Vec<&'static str>,
+) {
+    if field.is_none() && reference.is_some() {
+        *field = reference.clone();
+        filled.push(name);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use beerxml::hop::Use;
+
+    #[test]
+    fn bundled_library_parses() {
+        let library = Library::bundled();
+        assert!(library.hops.len() >= 20);
+        assert!(library.fermentables.len() >= 20);
+        assert!(library.yeasts.len() >= 10);
+        assert!(!library.miscs.is_empty());
+    }
+
+    #[test]
+    fn only_missing_fields_are_filled() {
+        let library = Library::bundled();
+        let mut hop = Hop::new("Cascade".into(), 7.0, 0.02, Use::Boil, 60.0);
+        hop.beta = Some(5.0);
+        let filled = complete_hop(&mut hop, library.hop("cascade").unwrap());
+        assert!(filled.contains(&"hsi") && !filled.contains(&"beta"));
+        assert_eq!(hop.alpha, 7.0);
+        assert_eq!(hop.beta, Some(5.0));
+        assert!(complete_hop(&mut hop, library.hop("Cascade").unwrap()).is_empty());
+    }
+}
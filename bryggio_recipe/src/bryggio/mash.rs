@@ -0,0 +1,267 @@
+//! # Mash profile
+//!
+//! Temperature of the mash over time, and the energy to heat it.
+//!
+//! The mash steps are expanded to a setpoint curve:
+//!
+//! - `Infusion` and `Decoction` steps jump to the step temperature
+//! - `Temperature` steps ramp to the step temperature in the ramp time,
+//!   or at 1 °C/min if no ramp time is given
+//!
+//! Each step then holds for the step time, drifting to the end temperature if one is given.
+//! The mash starts at the temperature of the first step.
+//!
+//! Heating is modelled with the heat capacities of the water and the grain,
+//! ignoring the tun and losses to the surroundings.
+use crate::bryggio::recipe::{Recipe, RecipeSrc};
+use crate::bryggio::MashStep;
+use beerxml::mash::Type;
+use brew_calculator::units::*;
+use serde::Serialize;
+
+/// Specific heat of water, kJ/(kg K)
+pub(crate) const WATER_SPECIFIC_HEAT: f32 = 4.186;
+/// Specific heat of grain, kJ/(kg K)
+pub(crate) const GRAIN_SPECIFIC_HEAT: f32 = 1.7;
+/// Volume of one kilogram of grain in the mash, in liters
+pub(crate) const GRAIN_VOLUME: Liters = 0.67;
+const KILOJOULES_PER_KILOWATT_HOUR: f32 = 3600.0;
+/// Ramp rate of `Temperature` steps without a ramp time, °C/min
+const DEFAULT_RAMP_RATE: f32 = 1.0;
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+pub struct SetPoint {
+    /// Time from mashing in
+    pub time: Minutes,
+    pub temp: Celsius,
+}
+
+/// Setpoint curve of the mash, linear between the points
+///
+/// Jumps are two points at the same time.
+#[derive(Serialize, Clone, Debug, PartialEq, Default)]
+pub struct Profile {
+    pub points: Vec<SetPoint>,
+}
+
+impl Profile {
+    pub fn new<'a>(steps: impl IntoIterator<Item = &'a MashStep>) -> Self {
+        let mut points: Vec<SetPoint> = Vec::new();
+        let mut time = 0.0;
+        for step in steps {
+            if let Some(last) = points.last() {
+                if step.type_ == Type::Temperature {
+                    time += ramp_time(step, last.temp);
+                }
+            }
+            points.push(SetPoint {
+                time,
+                temp: step.step_temp,
+            });
+            time += step.step_time;
+            points.push(SetPoint {
+                time,
+                temp: step.end_temp.unwrap_or(step.step_temp),
+            });
+        }
+        Self { points }
+    }
+
+    /// Total time from mashing in to the end of the last step
+    pub fn duration(&self) -> Minutes {
+        self.points.last().map_or(0.0, |point| point.time)
+    }
+
+    /// Setpoint at `time`, the temperature after the jump at the time of a jump
+    pub fn temp_at(&self, time: Minutes) -> Option<Celsius> {
+        if time < 0.0 || time > self.duration() {
+            return None;
+        }
+        self.points
+            .windows(2)
+            .rev()
+            .find(|window| window[0].time <= time && time <= window[1].time)
+            .map(|window| {
+                let (start, end) = (window[0], window[1]);
+                if end.time > start.time {
+                    start.temp
+                        + (end.temp - start.temp) * (time - start.time) / (end.time - start.time)
+                } else {
+                    end.temp
+                }
+            })
+    }
+}
+
+/// Electric heating element of the mash tun or hot liquor tank
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+pub struct Heater {
+    /// kW
+    pub power: f32,
+    /// Share of the energy that ends up in the water or mash
+    pub efficiency: Percent,
+    /// Temperature of the water before heating
+    pub water_temp: Celsius,
+}
+
+impl Heater {
+    pub fn new(power: f32) -> Self {
+        Self {
+            power,
+            efficiency: 100.0,
+            water_temp: 20.0,
+        }
+    }
+
+    /// Minutes to deliver `energy` kWh
+    pub fn heating_time(&self, energy: f32) -> Minutes {
+        60.0 * energy / self.power
+    }
+}
+
+/// Energy for a mash step
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct Heating {
+    pub step: String,
+    /// Temperature the infused water is heated to, for `Infusion` steps
+    pub water_temp: Option<Celsius>,
+    /// kWh drawn by the heater
+    pub energy: f32,
+    /// Time to deliver the energy at the heater's full power
+    pub heating_time: Minutes,
+    /// Power needed to keep to the ramp time, for `Temperature` steps, kW
+    pub power: Option<f32>,
+}
+
+impl Heating {
+    /// False if the heater is too weak for the ramp time of a `Temperature` step
+    pub fn keeps_ramp(&self, heater: &Heater) -> bool {
+        self.power.is_none_or(|power| power <= heater.power)
+    }
+}
+
+/// Infusion water temperature that brings the mash to `target`
+///
+/// $T_w = T + \frac{C_m}{c_w V} (T - T_m)$, where $C_m$ is the heat capacity of
+/// the mash at $T_m$ before the infusion and $V$ the volume of infused water.
+pub fn infusion_temp(
+    target: Celsius,
+    mash_temp: Celsius,
+    mash_heat_capacity: f32,
+    water: Liters,
+) -> Celsius {
+    target + mash_heat_capacity * (target - mash_temp) / (WATER_SPECIFIC_HEAT * water)
+}
+
+/// Strike water temperature to mash in `grain` at `grain_temp` with `water` at `target`
+pub fn strike_temp(
+    target: Celsius,
+    grain_temp: Celsius,
+    water: Liters,
+    grain: Kilograms,
+) -> Celsius {
+    infusion_temp(target, grain_temp, heat_capacity(0.0, grain), water)
+}
+
+/// Heat capacity of a mash, kJ/K
+pub(crate) fn heat_capacity(water: Liters, grain: Kilograms) -> f32 {
+    WATER_SPECIFIC_HEAT * water + GRAIN_SPECIFIC_HEAT * grain
+}
+
+/// Energy to heat every step of the mash of `recipe` with `heater`
+///
+/// Infusion water is heated from the heater's water temperature,
+/// `Temperature` steps heat the whole mash. Decoctions are heated separately.
+pub fn heating<Src: RecipeSrc>(recipe: &Recipe<Src>, heater: &Heater) -> Vec<Heating> {
+    let grain = recipe.grain_mass();
+    let mut water = 0.0;
+    let mut temp = recipe.mash.grain_temp;
+    recipe
+        .mash_steps()
+        .map(|step| {
+            let kilojoules_to_kwh = |kilojoules: f32| {
+                kilojoules / KILOJOULES_PER_KILOWATT_HOUR * 100.0 / heater.efficiency
+            };
+            let (water_temp, energy, power) = match (step.type_, step.infuse_amount) {
+                (Type::Infusion, Some(infusion)) if infusion > 0.0 => {
+                    let water_temp =
+                        infusion_temp(step.step_temp, temp, heat_capacity(water, grain), infusion);
+                    water += infusion;
+                    let energy = kilojoules_to_kwh(
+                        WATER_SPECIFIC_HEAT * infusion * (water_temp - heater.water_temp).max(0.0),
+                    );
+                    (Some(water_temp), energy, None)
+                }
+                (Type::Temperature, _) => {
+                    let energy = kilojoules_to_kwh(
+                        heat_capacity(water, grain) * (step.step_temp - temp).max(0.0),
+                    );
+                    let ramp_time = ramp_time(step, temp);
+                    let power = if ramp_time > 0.0 {
+                        energy * 60.0 / ramp_time
+                    } else {
+                        f32::INFINITY
+                    };
+                    (None, energy, Some(power).filter(|_| energy > 0.0))
+                }
+                _ => (None, 0.0, None),
+            };
+            temp = step.end_temp.unwrap_or(step.step_temp);
+            Heating {
+                step: step.name.clone(),
+                water_temp,
+                energy,
+                heating_time: heater.heating_time(energy),
+                power,
+            }
+        })
+        .collect()
+}
+
+fn ramp_time(step: &MashStep, from: Celsius) -> Minutes {
+    step.ramp_time
+        .unwrap_or_else(|| (step.step_temp - from).abs() / DEFAULT_RAMP_RATE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(type_: Type, step_temp: Celsius, step_time: Minutes) -> MashStep {
+        MashStep {
+            name: String::new(),
+            version: 1,
+            type_,
+            infuse_amount: None,
+            step_temp,
+            step_time,
+            ramp_time: None,
+            end_temp: None,
+        }
+    }
+
+    #[test]
+    fn ramps_and_jumps() {
+        let mut rest = step(Type::Temperature, 72.0, 20.0);
+        rest.ramp_time = Some(8.0);
+        let steps = vec![
+            step(Type::Infusion, 64.0, 40.0),
+            rest,
+            step(Type::Temperature, 78.0, 10.0),
+            step(Type::Infusion, 80.0, 5.0),
+        ];
+        let profile = Profile::new(&steps);
+        // 40 + 8 + 20 + 6 + 10 + 5
+        assert_eq!(profile.duration(), 89.0);
+        assert_eq!(profile.temp_at(44.0), Some(68.0));
+        assert_eq!(profile.temp_at(84.0), Some(80.0));
+        assert_eq!(profile.temp_at(90.0), None);
+    }
+
+    #[test]
+    fn strike_water() {
+        // 4 kg grain at 20 °C in 12 l to 67 °C
+        let strike = strike_temp(67.0, 20.0, 12.0, 4.0);
+        assert!((strike - 73.36).abs() < 0.01);
+    }
+}
@@ -1,5 +1,25 @@
+pub mod analytics;
+pub mod batch;
+pub mod bitterness;
+pub mod cost;
+pub mod decoction;
+pub mod design;
+pub mod diastatic;
+pub mod diff;
+pub mod efficiency;
+pub mod enrich;
+pub mod hop_schedule;
+pub mod ingredient;
+pub mod inventory;
+pub mod library;
+pub mod mash;
+pub mod pitch;
 pub(crate) mod process;
+pub mod quantity;
 pub mod recipe;
+pub mod sparge;
+pub mod substitution;
+pub mod system;
 // mod state_machine;
 pub use recipe::Recipe;
 
@@ -15,4 +35,5 @@ type Water = beerxml::Water;
 type Equipment = beerxml::Equipment;
 type Style = beerxml::Style;
 type Misc = beerxml::Misc;
+type MashStep = beerxml::mash::MashStep;
 type Type = beerxml::Type;
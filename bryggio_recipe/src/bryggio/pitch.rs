@@ -0,0 +1,351 @@
+//! # Yeast pitching
+//!
+//! Required cell count, yeast viability and starter sizing.
+//!
+//! Cell counts are given in billions of cells and pitch rates in million cells per milliliter
+//! and degree Plato.
+//! The starter growth model is the one derived by Kai Troester (braukaiser.com),
+//! where growth is expressed in billion cells per gram of extract in the starter wort.
+use crate::bryggio::quantity::Gravity;
+use crate::bryggio::Yeast;
+use beerxml::yeast::{Form, Type};
+use brew_calculator::units::*;
+
+/// Cell count in billions of cells
+pub type BillionCells = f32;
+
+/// Pitch rate for ales, million cells / (ml °P)
+const ALE_PITCH_RATE: f32 = 0.75;
+/// Pitch rate for lagers, million cells / (ml °P)
+const LAGER_PITCH_RATE: f32 = 1.5;
+/// Gravity above which the pitch rate is increased.
+const HIGH_GRAVITY: SpecificGravity = 1.060;
+/// Extra pitch rate for high gravity worts, million cells / (ml °P)
+const HIGH_GRAVITY_EXTRA_PITCH_RATE: f32 = 0.25;
+
+/// Cells in a fresh liquid yeast pack or vial.
+const LIQUID_PACK_CELLS: BillionCells = 100.0;
+/// Weight of a dry yeast sachet in grams.
+const DRY_PACK_WEIGHT: f32 = 11.5;
+/// Cells per gram of dry yeast.
+const DRY_CELLS_PER_GRAM: BillionCells = 10.0;
+/// Cells picked from a slant or a culture, which always needs a starter.
+const CULTURE_CELLS: BillionCells = 0.1;
+
+/// Viability of liquid yeast at the manufacture date.
+const LIQUID_VIABILITY: Percent = 97.0;
+/// Viability lost per day of storage for liquid yeast.
+const LIQUID_VIABILITY_LOSS: Percent = 0.7;
+/// Viability of dry yeast at the manufacture date.
+const DRY_VIABILITY: Percent = 100.0;
+/// Viability lost per day of storage for dry yeast.
+const DRY_VIABILITY_LOSS: Percent = 0.13;
+/// Viability lost per generation of harvested yeast.
+const GENERATION_VIABILITY_LOSS: Percent = 5.0;
+
+/// Growth on a stir plate when the inoculation rate is low, billion cells / g extract
+const STIR_PLATE_MAX_GROWTH: f32 = 1.4;
+/// Inoculation rate above which no growth occurs, billion cells / g extract
+const NO_GROWTH_INOCULATION_RATE: f32 = 3.5;
+/// Growth with intermittent shaking relative to a stir plate.
+const SHAKING_GROWTH_FACTOR: f32 = 0.6;
+
+/// How the starter is kept aerated
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Aeration {
+    StirPlate,
+    /// Intermittent shaking of the starter vessel
+    Shaking,
+}
+
+/// Starter setup available to the brewer
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct StarterParams {
+    pub aeration: Aeration,
+    /// Gravity of the starter wort
+    pub gravity: SpecificGravity,
+    /// Largest volume that fits in the starter vessel
+    pub max_step_volume: Liters,
+    /// Largest number of steps the brewer is willing to make
+    pub max_steps: usize,
+}
+
+impl Default for StarterParams {
+    fn default() -> Self {
+        Self {
+            aeration: Aeration::StirPlate,
+            gravity: 1.036,
+            max_step_volume: 2.0,
+            max_steps: 3,
+        }
+    }
+}
+
+/// A single starter step
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct StarterStep {
+    pub volume: Liters,
+    /// Extract in the starter wort in grams
+    pub extract: f32,
+    pub initial_cells: BillionCells,
+    pub final_cells: BillionCells,
+}
+
+/// A one- or multi-step starter
+#[derive(Clone, Debug, PartialEq)]
+pub struct Starter {
+    /// Number of packs used to inoculate the first step
+    pub packs: u32,
+    pub steps: Vec<StarterStep>,
+}
+
+impl Starter {
+    pub fn final_cells(&self) -> BillionCells {
+        self.steps.last().map_or(0.0, |step| step.final_cells)
+    }
+}
+
+/// Pitching plan for a single yeast
+#[derive(Clone, Debug, PartialEq)]
+pub struct PitchPlan {
+    pub name: String,
+    pub product_id: Option<String>,
+    /// Million cells / (ml °P)
+    pub pitch_rate: f32,
+    pub required_cells: BillionCells,
+    pub viability: Percent,
+    /// Viable cells in a single pack, or in the recipe amount of dry yeast given by weight
+    pub cells_per_pack: BillionCells,
+    /// Number of packs needed when pitching directly without a starter.
+    /// 0 if the yeast has no viable cells left.
+    pub packs: u32,
+    /// Starter needed to reach the required cell count, if starter parameters were given.
+    /// `None` if the direct pitch only needs a single pack or no starter can be made.
+    pub starter: Option<Starter>,
+}
+
+/// Recommended pitch rate
+///
+/// Million cells per milliliter and degree Plato, increased for high gravity worts.
+pub fn pitch_rate(type_: Type, og: SpecificGravity) -> f32 {
+    let base = match type_ {
+        Type::Lager => LAGER_PITCH_RATE,
+        Type::Ale | Type::Wheat | Type::Wine | Type::Champagne => ALE_PITCH_RATE,
+    };
+    if og > HIGH_GRAVITY {
+        base + HIGH_GRAVITY_EXTRA_PITCH_RATE
+    } else {
+        base
+    }
+}
+
+/// Required number of cells to pitch into `volume` of wort
+///
+/// $N = r \cdot V \cdot P$, with pitch rate $r$, volume $V$ in ml and wort strength $P$ in °P.
+pub fn required_cells(type_: Type, og: SpecificGravity, volume: Liters) -> BillionCells {
+    pitch_rate(type_, og) * volume * Gravity::from_specific_gravity(og).plato()
+}
+
+/// Estimated viability
+///
+/// Linear loss of viability with `age` (days since manufacture), depending on the yeast form,
+/// and a further loss for every generation the yeast has been harvested.
+pub fn viability(form: Form, age: Days, times_cultured: Option<u8>) -> Percent {
+    let fresh = match form {
+        Form::Dry => DRY_VIABILITY - DRY_VIABILITY_LOSS * age,
+        Form::Liquid | Form::Slant | Form::Culture => {
+            LIQUID_VIABILITY - LIQUID_VIABILITY_LOSS * age
+        }
+    };
+    let generations = f32::from(times_cultured.unwrap_or(0));
+    (fresh - GENERATION_VIABILITY_LOSS * generations).max(0.0)
+}
+
+/// Cells in a single fresh pack of yeast, disregarding viability
+pub fn cells_per_pack(form: Form) -> BillionCells {
+    match form {
+        Form::Liquid => LIQUID_PACK_CELLS,
+        Form::Dry => DRY_PACK_WEIGHT * DRY_CELLS_PER_GRAM,
+        Form::Slant | Form::Culture => CULTURE_CELLS,
+    }
+}
+
+/// Cells in a single pitch of `yeast`, disregarding viability
+///
+/// The amount of dry yeast given by weight, otherwise a single pack of its form.
+fn pitch_cells(yeast: &Yeast) -> BillionCells {
+    match yeast.form {
+        Form::Dry if yeast.amount_is_weight == Some(true) && yeast.amount > 0.0 => {
+            yeast.amount * 1000.0 * DRY_CELLS_PER_GRAM
+        }
+        form => cells_per_pack(form),
+    }
+}
+
+/// Cell growth in a starter
+///
+/// Growth in billion cells per gram of extract as a function of the inoculation rate
+/// (billion cells per gram of extract).
+fn growth_rate(inoculation_rate: f32, aeration: Aeration) -> f32 {
+    let stir_plate = if inoculation_rate < STIR_PLATE_MAX_GROWTH {
+        STIR_PLATE_MAX_GROWTH
+    } else if inoculation_rate < NO_GROWTH_INOCULATION_RATE {
+        2.33 - 0.67 * inoculation_rate
+    } else {
+        0.0
+    };
+    match aeration {
+        Aeration::StirPlate => stir_plate,
+        Aeration::Shaking => SHAKING_GROWTH_FACTOR * stir_plate,
+    }
+}
+
+/// Extract in grams in `volume` of wort with specific gravity `gravity`
+fn extract(volume: Liters, gravity: SpecificGravity) -> f32 {
+    volume * 1000.0 * gravity * Gravity::from_specific_gravity(gravity).plato() / 100.0
+}
+
+fn starter_step(
+    initial_cells: BillionCells,
+    volume: Liters,
+    params: &StarterParams,
+) -> StarterStep {
+    let extract = extract(volume, params.gravity);
+    let final_cells = if extract > 0.0 && initial_cells > 0.0 {
+        initial_cells + extract * growth_rate(initial_cells / extract, params.aeration)
+    } else {
+        initial_cells
+    };
+    StarterStep {
+        volume,
+        extract,
+        initial_cells,
+        final_cells,
+    }
+}
+
+/// Starter steps growing `initial_cells` to `required_cells`
+///
+/// Every step but the last uses the full `max_step_volume`, the last step is sized to just
+/// reach the required cell count.
+/// Returns `None` if the required count cannot be reached within `max_steps`.
+pub fn starter_steps(
+    initial_cells: BillionCells,
+    required_cells: BillionCells,
+    params: &StarterParams,
+) -> Option<Vec<StarterStep>> {
+    let mut steps = Vec::new();
+    let mut cells = initial_cells;
+    while cells < required_cells {
+        if steps.len() == params.max_steps {
+            return None;
+        }
+        let full_step = starter_step(cells, params.max_step_volume, params);
+        if full_step.final_cells <= cells {
+            return None;
+        }
+        let step = if full_step.final_cells < required_cells {
+            full_step
+        } else {
+            // Final cell count is increasing in volume, bisect for the smallest sufficient step.
+            let (mut low, mut high) = (0.0, params.max_step_volume);
+            for _ in 0..30 {
+                let mid = (low + high) / 2.0;
+                if starter_step(cells, mid, params).final_cells < required_cells {
+                    low = mid;
+                } else {
+                    high = mid;
+                }
+            }
+            starter_step(cells, high, params)
+        };
+        cells = step.final_cells;
+        steps.push(step);
+    }
+    Some(steps)
+}
+
+/// Pitching plan for `yeast` in `volume` of wort
+///
+/// - `og`: original gravity of the wort
+/// - `age`: days since the yeast was manufactured
+/// - `starter`: available starter setup, `None` if only direct pitching is considered
+pub fn plan(
+    yeast: &Yeast,
+    og: SpecificGravity,
+    volume: Liters,
+    age: Days,
+    starter: Option<&StarterParams>,
+) -> PitchPlan {
+    let required_cells = required_cells(yeast.type_, og, volume);
+    let viability = viability(yeast.form, age, yeast.times_cultured);
+    let cells_per_pack = pitch_cells(yeast) * viability / 100.0;
+    let packs = if cells_per_pack > 0.0 {
+        (required_cells / cells_per_pack).ceil().max(1.0) as u32
+    } else {
+        0
+    };
+    let starter = match starter {
+        Some(params) if packs > 1 => (1..packs).find_map(|starter_packs| {
+            let initial_cells = starter_packs as f32 * cells_per_pack;
+            starter_steps(initial_cells, required_cells, params).map(|steps| Starter {
+                packs: starter_packs,
+                steps,
+            })
+        }),
+        _ => None,
+    };
+    PitchPlan {
+        name: yeast.name.clone(),
+        product_id: yeast.product_id.clone(),
+        pitch_rate: pitch_rate(yeast.type_, og),
+        required_cells,
+        viability,
+        cells_per_pack,
+        packs,
+        starter,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ale_pitch() {
+        let cells = required_cells(Type::Ale, 1.048, 20.0);
+        assert!((cells - 178.5).abs() < 1.0, "{}", cells);
+        assert_eq!(pitch_rate(Type::Lager, 1.070), 1.75);
+    }
+
+    #[test]
+    fn viability_never_negative() {
+        assert_eq!(viability(Form::Liquid, 0.0, None), 97.0);
+        assert_eq!(viability(Form::Liquid, 365.0, Some(2)), 0.0);
+    }
+
+    #[test]
+    fn single_step_starter() {
+        let params = StarterParams::default();
+        let steps = starter_steps(90.0, 200.0, &params).unwrap();
+        assert_eq!(steps.len(), 1);
+        assert!(steps[0].volume < params.max_step_volume);
+        assert!(steps[0].final_cells >= 200.0);
+    }
+
+    #[test]
+    fn multi_step_starter() {
+        let params = StarterParams {
+            max_step_volume: 0.5,
+            ..StarterParams::default()
+        };
+        let steps = starter_steps(CULTURE_CELLS, 100.0, &params).unwrap();
+        assert!(steps.len() > 1);
+        assert!(steps.last().unwrap().final_cells >= 100.0);
+    }
+
+    #[test]
+    fn dead_yeast_does_not_grow() {
+        assert_eq!(starter_steps(0.0, 100.0, &StarterParams::default()), None);
+    }
+}
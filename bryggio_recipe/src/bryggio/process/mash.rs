@@ -1,3 +1,32 @@
+use crate::bryggio::MashStep;
+use brew_calculator::units::*;
 use serde::Deserialize;
+
+/// Grain temperature assumed when the recipe doesn't give one
+const ROOM_TEMP: Celsius = 20.0;
+
 #[derive(Deserialize, Debug, PartialEq)]
-pub struct Mash {}
+pub struct Mash {
+    /// Temperature of the grain before mashing in
+    pub grain_temp: Celsius,
+    pub sparge_temp: Option<Celsius>,
+    pub steps: Vec<MashStep>,
+}
+
+impl Mash {
+    pub(crate) fn from_beerxml_mash(mash: beerxml::mash::Mash) -> Self {
+        Self {
+            grain_temp: mash.grain_temp,
+            sparge_temp: mash.sparge_temp,
+            steps: mash.mash_steps.mash_step,
+        }
+    }
+
+    pub(crate) fn from_steps(steps: Vec<MashStep>) -> Self {
+        Self {
+            grain_temp: ROOM_TEMP,
+            sparge_temp: None,
+            steps,
+        }
+    }
+}
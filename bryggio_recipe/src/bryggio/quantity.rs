@@ -0,0 +1,341 @@
+//! # Quantities
+//!
+//! Strongly typed quantities with conversions between metric and imperial units.
+//!
+//! All quantities are stored in the units used by BeerXML and `brew_calculator::units`,
+//! and are formatted for display in a user-selected [`UnitSystem`](enum.UnitSystem.html).
+use brew_calculator::units::*;
+use std::fmt;
+
+const POUNDS_PER_KILOGRAM: f32 = 2.204_623;
+const OUNCES_PER_POUND: f32 = 16.0;
+const LITERS_PER_GALLON: f32 = 3.785_412;
+const PSI_PER_BAR: f32 = 14.503_77;
+const EBC_PER_SRM: f32 = 1.97;
+
+/// Unit system used when displaying quantities
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+pub enum UnitSystem {
+    /// kg, l, °C, °P, EBC and bar
+    #[default]
+    Metric,
+    /// lb/oz, US gal, °F, specific gravity, SRM and psi
+    Imperial,
+}
+
+/// Quantity that can be displayed in a unit system
+pub trait Quantity: Copy {
+    /// Value and unit in `system`
+    fn format(&self, system: UnitSystem) -> String;
+
+    /// Wrapper implementing `Display` in `system`
+    fn display(&self, system: UnitSystem) -> Display<Self> {
+        Display {
+            quantity: *self,
+            system,
+        }
+    }
+}
+
+/// Quantity displayed in a unit system
+#[derive(Copy, Clone, Debug)]
+pub struct Display<Q: Quantity> {
+    quantity: Q,
+    system: UnitSystem,
+}
+
+impl<Q: Quantity> fmt::Display for Display<Q> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.quantity.format(self.system))
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd, Default)]
+pub struct Mass(Kilograms);
+
+impl Mass {
+    pub fn from_kilograms(kilograms: Kilograms) -> Self {
+        Mass(kilograms)
+    }
+
+    pub fn from_grams(grams: f32) -> Self {
+        Mass(grams / 1000.0)
+    }
+
+    pub fn from_pounds(pounds: f32) -> Self {
+        Mass(pounds / POUNDS_PER_KILOGRAM)
+    }
+
+    pub fn from_ounces(ounces: f32) -> Self {
+        Mass::from_pounds(ounces / OUNCES_PER_POUND)
+    }
+
+    pub fn kilograms(self) -> Kilograms {
+        self.0
+    }
+
+    pub fn grams(self) -> f32 {
+        self.0 * 1000.0
+    }
+
+    pub fn pounds(self) -> f32 {
+        self.0 * POUNDS_PER_KILOGRAM
+    }
+
+    pub fn ounces(self) -> f32 {
+        self.pounds() * OUNCES_PER_POUND
+    }
+}
+
+impl Quantity for Mass {
+    /// Small amounts, like hops, are shown in g or oz.
+    fn format(&self, system: UnitSystem) -> String {
+        match system {
+            UnitSystem::Metric if self.0 < 1.0 => format!("{:.0} g", self.grams()),
+            UnitSystem::Metric => format!("{:.2} kg", self.kilograms()),
+            UnitSystem::Imperial if self.pounds() < 1.0 => format!("{:.2} oz", self.ounces()),
+            UnitSystem::Imperial => format!("{:.2} lb", self.pounds()),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd, Default)]
+pub struct Volume(Liters);
+
+impl Volume {
+    pub fn from_liters(liters: Liters) -> Self {
+        Volume(liters)
+    }
+
+    /// US gallons
+    pub fn from_gallons(gallons: f32) -> Self {
+        Volume(gallons * LITERS_PER_GALLON)
+    }
+
+    pub fn liters(self) -> Liters {
+        self.0
+    }
+
+    pub fn milliliters(self) -> f32 {
+        self.0 * 1000.0
+    }
+
+    /// US gallons
+    pub fn gallons(self) -> f32 {
+        self.0 / LITERS_PER_GALLON
+    }
+}
+
+impl Quantity for Volume {
+    fn format(&self, system: UnitSystem) -> String {
+        match system {
+            UnitSystem::Metric => format!("{:.2} l", self.liters()),
+            UnitSystem::Imperial => format!("{:.2} gal", self.gallons()),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd, Default)]
+pub struct Temperature(Celsius);
+
+impl Temperature {
+    pub fn from_celsius(celsius: Celsius) -> Self {
+        Temperature(celsius)
+    }
+
+    pub fn from_fahrenheit(fahrenheit: f32) -> Self {
+        Temperature((fahrenheit - 32.0) / 1.8)
+    }
+
+    pub fn celsius(self) -> Celsius {
+        self.0
+    }
+
+    pub fn fahrenheit(self) -> f32 {
+        self.0 * 1.8 + 32.0
+    }
+}
+
+impl Quantity for Temperature {
+    fn format(&self, system: UnitSystem) -> String {
+        match system {
+            UnitSystem::Metric => format!("{:.1} °C", self.celsius()),
+            UnitSystem::Imperial => format!("{:.1} °F", self.fahrenheit()),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
+pub struct Gravity(SpecificGravity);
+
+impl Gravity {
+    pub fn from_specific_gravity(sg: SpecificGravity) -> Self {
+        Gravity(sg)
+    }
+
+    /// Gravity from degrees Plato
+    ///
+    /// $SG = 1 + \frac{P}{258.6 - 227.1 P / 258.2}$
+    pub fn from_plato(plato: f32) -> Self {
+        Gravity(1.0 + plato / (258.6 - plato / 258.2 * 227.1))
+    }
+
+    /// Gravity points, $1000 (SG - 1)$
+    pub fn from_points(points: f32) -> Self {
+        Gravity(1.0 + points / 1000.0)
+    }
+
+    pub fn specific_gravity(self) -> SpecificGravity {
+        self.0
+    }
+
+    /// Degrees Plato
+    pub fn plato(self) -> f32 {
+        -616.868 + 1111.14 * self.0 - 630.272 * self.0.powi(2) + 135.997 * self.0.powi(3)
+    }
+
+    /// Gravity points, $1000 (SG - 1)$
+    pub fn points(self) -> f32 {
+        1000.0 * (self.0 - 1.0)
+    }
+}
+
+impl Default for Gravity {
+    fn default() -> Self {
+        Gravity(1.0)
+    }
+}
+
+impl Quantity for Gravity {
+    fn format(&self, system: UnitSystem) -> String {
+        match system {
+            UnitSystem::Metric => format!("{:.1} °P", self.plato()),
+            UnitSystem::Imperial => format!("{:.3}", self.specific_gravity()),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd, Default)]
+pub struct Color(SRMColor);
+
+impl Color {
+    pub fn from_srm(srm: SRMColor) -> Self {
+        Color(srm)
+    }
+
+    pub fn from_ebc(ebc: f32) -> Self {
+        Color(ebc / EBC_PER_SRM)
+    }
+
+    /// Color from degrees Lovibond, as used for grains
+    ///
+    /// $SRM = 1.3546 L - 0.76$
+    pub fn from_lovibond(lovibond: f32) -> Self {
+        Color(1.3546 * lovibond - 0.76)
+    }
+
+    pub fn srm(self) -> SRMColor {
+        self.0
+    }
+
+    pub fn ebc(self) -> f32 {
+        self.0 * EBC_PER_SRM
+    }
+
+    pub fn lovibond(self) -> f32 {
+        (self.0 + 0.76) / 1.3546
+    }
+}
+
+impl Quantity for Color {
+    fn format(&self, system: UnitSystem) -> String {
+        match system {
+            UnitSystem::Metric => format!("{:.0} EBC", self.ebc()),
+            UnitSystem::Imperial => format!("{:.1} SRM", self.srm()),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd, Default)]
+pub struct Pressure(f32);
+
+impl Pressure {
+    pub fn from_bar(bar: f32) -> Self {
+        Pressure(bar)
+    }
+
+    pub fn from_kilopascals(kilopascals: f32) -> Self {
+        Pressure(kilopascals / 100.0)
+    }
+
+    pub fn from_psi(psi: f32) -> Self {
+        Pressure(psi / PSI_PER_BAR)
+    }
+
+    pub fn bar(self) -> f32 {
+        self.0
+    }
+
+    pub fn kilopascals(self) -> f32 {
+        self.0 * 100.0
+    }
+
+    pub fn psi(self) -> f32 {
+        self.0 * PSI_PER_BAR
+    }
+}
+
+impl Quantity for Pressure {
+    fn format(&self, system: UnitSystem) -> String {
+        match system {
+            UnitSystem::Metric => format!("{:.2} bar", self.bar()),
+            UnitSystem::Imperial => format!("{:.1} psi", self.psi()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: f32, b: f32, tol: f32) {
+        assert!((a - b).abs() < tol, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn round_trips() {
+        assert_close(
+            Mass::from_pounds(Mass::from_kilograms(2.5).pounds()).kilograms(),
+            2.5,
+            1e-5,
+        );
+        assert_close(Volume::from_gallons(5.0).liters(), 18.93, 1e-2);
+        assert_close(Temperature::from_fahrenheit(152.0).celsius(), 66.67, 1e-2);
+        assert_close(
+            Color::from_ebc(Color::from_srm(10.0).ebc()).srm(),
+            10.0,
+            1e-5,
+        );
+        assert_close(Pressure::from_psi(14.5).bar(), 1.0, 1e-3);
+    }
+
+    #[test]
+    fn plato() {
+        let gravity = Gravity::from_specific_gravity(1.048);
+        assert_close(gravity.plato(), 11.9, 0.05);
+        assert_close(
+            Gravity::from_plato(gravity.plato()).specific_gravity(),
+            1.048,
+            1e-3,
+        );
+    }
+
+    #[test]
+    fn format_in_unit_system() {
+        let hops = Mass::from_grams(28.35);
+        assert_eq!(hops.display(UnitSystem::Metric).to_string(), "28 g");
+        assert_eq!(hops.display(UnitSystem::Imperial).to_string(), "1.00 oz");
+        let og = Gravity::from_specific_gravity(1.050);
+        assert_eq!(og.format(UnitSystem::Imperial), "1.050");
+    }
+}
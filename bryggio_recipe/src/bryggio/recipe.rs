@@ -1,9 +1,25 @@
 use thiserror::Error;
 
-use crate::bryggio::{process, Equipment, Fermentable, Hop, Misc, Style, Type, Water, Yeast};
+use crate::bryggio::{
+    bitterness, cost, decoction, design, diastatic, diff, efficiency, mash, pitch, process,
+    quantity::{Color, Gravity, Mass, Quantity, UnitSystem, Volume},
+    sparge,
+    substitution::{self, Substitution},
+    system::System,
+    Equipment, Fermentable, Hop, MashStep, Misc, Style, Type, Water, Yeast,
+};
 use brew_calculator::units::*;
-use brew_calculator::{ibu, ibu::IbuCalc, utils};
+use brew_calculator::{ibu, utils};
 use serde::Deserialize;
+use std::fmt;
+
+/// Gravity points from one kilogram of sucrose dissolved to one liter
+const SUCROSE_POTENTIAL: f32 = 384.0;
+
+/// Apparent attenuation assumed for yeasts without a specified attenuation
+const DEFAULT_ATTENUATION: Percent = 75.0;
+/// Brewhouse efficiency assumed for recipes without a specified efficiency
+pub const DEFAULT_EFFICIENCY: Percent = 72.0;
 
 #[derive(Deserialize, Debug, PartialEq)]
 #[serde(rename_all = "UPPERCASE")]
@@ -35,6 +51,13 @@ pub struct Recipe<Src: RecipeSrc> {
     pub(crate) taste_rating: Option<f32>,
     pub(crate) date: Option<String>,
     pub(crate) ibu_method: ibu::Method,
+    /// Hop storage used to age the alpha acids in IBU calculations
+    #[serde(skip)]
+    pub(crate) hop_storage: Option<bitterness::HopStorage>,
+    /// Brewing system used in volume and efficiency calculations,
+    /// mapped from the equipment of the source recipe
+    #[serde(skip)]
+    pub(crate) system: System,
     pub(crate) recipe_src: Src,
 }
 
@@ -59,21 +82,391 @@ impl<Src: RecipeSrc> Recipe<Src> {
         self.miscs.iter()
     }
 
+    /// Mash steps in order
+    pub fn mash_steps(&self) -> std::slice::Iter<'_, MashStep> {
+        self.mash.steps.iter()
+    }
+
+    /// Mass of the grains and adjuncts in the mash
+    pub fn grain_mass(&self) -> Kilograms {
+        self.fermentables()
+            .filter(|fermentable| fermentable.type_.mashed())
+            .map(|fermentable| fermentable.amount)
+            .sum()
+    }
+
+    /// Original values in the source recipe
+    pub fn recipe_src(&self) -> &Src {
+        &self.recipe_src
+    }
+
+    /// Recipe with the source replaced by `f(src)`
+    pub fn map_src<T: RecipeSrc, F: FnOnce(Src) -> T>(self, f: F) -> Recipe<T> {
+        Recipe {
+            name: self.name,
+            type_: self.type_,
+            style: self.style,
+            brewer: self.brewer,
+            asst_brewer: self.asst_brewer,
+            equipment: self.equipment,
+            batch_size: self.batch_size,
+            pre_boil_gravity: self.pre_boil_gravity,
+            og: self.og,
+            fg: self.fg,
+            efficiency: self.efficiency,
+            hops: self.hops,
+            fermentables: self.fermentables,
+            yeasts: self.yeasts,
+            waters: self.waters,
+            miscs: self.miscs,
+            mash: self.mash,
+            boil: self.boil,
+            fermentation: self.fermentation,
+            carbonation: self.carbonation,
+            notes: self.notes,
+            taste_notes: self.taste_notes,
+            taste_rating: self.taste_rating,
+            date: self.date,
+            ibu_method: self.ibu_method,
+            hop_storage: self.hop_storage,
+            system: self.system,
+            recipe_src: f(self.recipe_src),
+        }
+    }
+
+    /// Style the recipe is brewed to
+    pub fn style(&self) -> &Style {
+        &self.style
+    }
+
+    /// Target volume of the batch at the start of fermentation
+    pub fn batch_volume(&self) -> Volume {
+        Volume::from_liters(self.batch_size)
+    }
+
+    /// Volume at the start of the boil
+    pub fn pre_boil_volume(&self) -> Volume {
+        Volume::from_liters(self.boil.pre_volume)
+    }
+
+    /// Pre-boil volume of the equipment, if any
+    pub fn equipment_boil_volume(&self) -> Option<Volume> {
+        self.equipment
+            .as_ref()
+            .map(|equipment| Volume::from_liters(equipment.boil_size))
+    }
+
+    /// Volume of the mash tun of the equipment
+    pub fn tun_volume(&self) -> Option<Volume> {
+        self.equipment
+            .as_ref()
+            .and_then(|equipment| equipment.tun_volume)
+            .map(Volume::from_liters)
+    }
+
+    /// Weight of the mash tun of the equipment
+    pub fn tun_weight(&self) -> Option<Mass> {
+        self.equipment
+            .as_ref()
+            .and_then(|equipment| equipment.tun_weight)
+            .map(Mass::from_kilograms)
+    }
+
+    /// Summary of the recipe with quantities in `system`
+    pub fn display(&self, system: UnitSystem) -> RecipeDisplay<'_, Src> {
+        RecipeDisplay {
+            recipe: self,
+            system,
+        }
+    }
+
+    /// Total boil time
+    pub fn boil_time(&self) -> Minutes {
+        self.boil.boil_time
+    }
+
+    /// Scale the recipe to a new batch size
+    ///
+    /// Ingredient amounts and the boil volume are scaled in proportion.
+    pub fn scale(&mut self, batch_size: Liters) {
+        let factor = batch_size / self.batch_size;
+        self.batch_size = batch_size;
+        self.boil.pre_volume *= factor;
+        self.fermentables
+            .iter_mut()
+            .for_each(|fermentable| fermentable.amount *= factor);
+        self.hops.iter_mut().for_each(|hop| hop.amount *= factor);
+        self.yeasts
+            .iter_mut()
+            .for_each(|yeast| yeast.amount *= factor);
+        self.miscs.iter_mut().for_each(|misc| misc.amount *= factor);
+    }
+
+    /// Original gravity, estimated from the recipe if not specified
+    pub fn original_gravity(&self) -> Gravity {
+        Gravity::from_specific_gravity(self.og_or_estimated())
+    }
+
+    /// Override the original gravity used in calculations
+    pub fn set_og(&mut self, og: SpecificGravity) {
+        self.og = Some(og);
+    }
+
+    /// Specified final gravity
+    pub fn final_gravity(&self) -> Option<Gravity> {
+        self.fg.map(Gravity::from_specific_gravity)
+    }
+
+    /// Final gravity, estimated from the yeast attenuation if not specified
+    pub fn estimated_fg(&self) -> SpecificGravity {
+        if let Some(fg) = self.fg {
+            return fg;
+        }
+        let og = self.og_or_estimated();
+        og - (og - 1.0) * self.attenuation() / 100.0
+    }
+
+    /// Highest attenuation of the yeasts
+    fn attenuation(&self) -> Percent {
+        self.yeasts()
+            .filter_map(|yeast| yeast.attenuation)
+            .fold(None, |max: Option<Percent>, att| {
+                Some(max.map_or(att, |max| max.max(att)))
+            })
+            .unwrap_or(DEFAULT_ATTENUATION)
+    }
+
+    /// Alcohol by volume in percent
+    ///
+    /// $ABV = 131.25 (OG - FG)$
+    pub fn abv(&self) -> Abv {
+        131.25 * (self.og_or_estimated() - self.estimated_fg())
+    }
+
+    /// Alcohol by volume in percent, from the estimated OG and the yeast attenuation
+    ///
+    /// Unlike [`abv`](#method.abv), specified gravities are ignored,
+    /// so it follows changes to the ingredients.
+    pub fn estimated_abv(&self) -> Abv {
+        131.25 * (self.estimated_og() - 1.0) * self.attenuation() / 100.0
+    }
+
+    /// Brewhouse efficiency in percent
+    pub fn efficiency(&self) -> Percent {
+        self.efficiency
+    }
+
+    /// Override the brewhouse efficiency used in calculations,
+    /// e.g. with one measured with [`efficiency_breakdown`](#method.efficiency_breakdown)
+    /// on the recipe's system
+    pub fn set_efficiency(&mut self, efficiency: Percent) {
+        self.efficiency = efficiency;
+    }
+
+    /// Conversion, mash, lauter and brewhouse efficiency from brew day readings
+    pub fn efficiency_breakdown(
+        &self,
+        readings: &efficiency::Readings,
+    ) -> efficiency::EfficiencyBreakdown {
+        efficiency::EfficiencyBreakdown::new(self, readings)
+    }
+
+    /// Replace all additions of a hop with a substitution from [`substitution::hops`]
+    pub fn substitute_hop(&mut self, substitution: Substitution<Hop>) {
+        substitution::apply(&mut self.hops, |hop| &hop.name, substitution);
+    }
+
+    /// Replace all additions of a fermentable with a substitution from
+    /// [`substitution::fermentables`]
+    ///
+    /// The base malt is scaled to keep the gravity points of the recipe.
+    pub fn substitute_fermentable(&mut self, substitution: Substitution<Fermentable>) {
+        let points: f32 = self
+            .fermentables()
+            .map(|fermentable| self.extract_points(fermentable))
+            .sum();
+        let replacement = substitution.name.clone();
+        substitution::apply(
+            &mut self.fermentables,
+            |fermentable| &fermentable.name,
+            substitution,
+        );
+        substitution::rebalance_base_malt(self, points, &replacement);
+    }
+
+    /// Replace a yeast with a substitution from [`substitution::yeasts`]
+    pub fn substitute_yeast(&mut self, substitution: Substitution<Yeast>) {
+        substitution::apply(&mut self.yeasts, |yeast| &yeast.name, substitution);
+    }
+
+    /// Price of brewing a batch, from the prices in `prices`
+    pub fn cost(&self, prices: &cost::PriceList) -> cost::Cost {
+        cost::Cost::new(self, prices)
+    }
+
+    /// Solve the fermentable and hop amounts of `design` for `targets`
+    pub fn design(
+        &mut self,
+        design: &design::Design,
+        targets: &design::Targets,
+    ) -> Result<(), design::DesignError> {
+        design.apply(self, targets)
+    }
+
+    /// Setpoint curve of the mash steps
+    pub fn mash_profile(&self) -> mash::Profile {
+        mash::Profile::new(self.mash_steps())
+    }
+
+    /// Energy to heat each mash step with `heater`
+    pub fn mash_heating(&self, heater: &mash::Heater) -> Vec<mash::Heating> {
+        mash::heating(self, heater)
+    }
+
+    /// Pull volumes and schedules for the decoction steps of the mash
+    pub fn decoctions(
+        &self,
+        params: &decoction::DecoctionParams,
+    ) -> Result<Vec<decoction::Decoction>, decoction::DecoctionError> {
+        decoction::plan(self, params)
+    }
+
+    /// Water volumes and runnings for lautering with `method`
+    pub fn sparge_plan(
+        &self,
+        method: sparge::Method,
+        params: &sparge::SpargeParams,
+    ) -> Result<sparge::SpargePlan, sparge::SpargeError> {
+        sparge::SpargePlan::new(self, method, params)
+    }
+
+    /// Water volumes and runnings for lautering with the sparge method of the recipe's system
+    pub fn lauter_plan(
+        &self,
+        params: &sparge::SpargeParams,
+    ) -> Result<sparge::SpargePlan, sparge::SpargeError> {
+        self.sparge_plan(self.system().sparge, params)
+    }
+
+    /// Diastatic power of the grist and whether its starch converts
+    pub fn conversion(&self) -> diastatic::Conversion {
+        diastatic::Conversion::new(self)
+    }
+
+    /// IBU method specified for the recipe
+    pub fn ibu_method(&self) -> ibu::Method {
+        self.ibu_method
+    }
+
+    /// Hop storage used to age the alpha acids in IBU calculations, if any
+    pub fn hop_storage(&self) -> Option<bitterness::HopStorage> {
+        self.hop_storage
+    }
+
+    /// Use the alpha acids of hops aged by `storage` in IBU calculations,
+    /// or the specified alpha acids if `None`
+    pub fn set_hop_storage(&mut self, storage: Option<bitterness::HopStorage>) {
+        self.hop_storage = storage;
+    }
+
+    /// Brewing system the recipe is brewed on
+    pub fn system(&self) -> System {
+        self.system
+    }
+
+    /// Brew on `system`
+    ///
+    /// The brewhouse efficiency is scaled by the ratio of the lauter efficiencies
+    /// of the two systems, so the estimated gravities follow the system.
+    pub fn set_system(&mut self, system: System) {
+        let params = sparge::SpargeParams::default();
+        let before = self.lauter_plan(&params);
+        self.system = system;
+        if let (Ok(before), Ok(after)) = (before, self.lauter_plan(&params)) {
+            self.efficiency *= after.lauter_efficiency / before.lauter_efficiency;
+        }
+    }
+
     /// Total IBU for recipe
     ///
-    /// Calculates and sums the individual IBU contributions for all bittering hops.
+    /// Calculates and sums the individual IBU contributions for all bittering hops,
+    /// with the recipe's IBU method.
+    /// The alpha acids are aged with the recipe's hop storage, if set.
     pub fn ibu(&self) -> Ibu {
-        self.hops()
-            .filter(|hop| hop.bittering())
-            .fold(0.0, |acc, hop| {
-                acc + self.ibu_method.ibu(
-                    hop.amount,
-                    hop.alpha,
-                    self.average_boil_volume(hop.time),
-                    hop.time,
-                    self.average_specific_gravity(hop.time),
-                )
-            })
+        self.ibu_with_model(&bitterness::Model::Boil(self.ibu_method))
+    }
+
+    /// Total IBU for recipe with a specific IBU model
+    pub fn ibu_with_model(&self, model: &bitterness::Model) -> Ibu {
+        self.hops().map(|hop| self.hop_ibu(hop, model)).sum()
+    }
+
+    /// IBU contribution from a single hop addition
+    ///
+    /// Includes the large batch hop utilization of the equipment, if any.
+    pub fn hop_ibu(&self, hop: &Hop, model: &bitterness::Model) -> Ibu {
+        self.hop_contribution(hop, model).ibu
+    }
+
+    /// Per hop breakdown of the bitterness with a specific IBU model
+    pub fn ibu_report(&self, model: &bitterness::Model) -> bitterness::IbuReport {
+        let hops = self
+            .hops()
+            .map(|hop| self.hop_contribution(hop, model))
+            .collect();
+        bitterness::IbuReport::new(*model, hops, self.og_or_estimated())
+    }
+
+    /// Bitterness calculated with all supported IBU models, side-by-side
+    ///
+    /// The post-boil models use the same `hop_stand`.
+    pub fn ibu_comparison(&self, hop_stand: bitterness::HopStand) -> Vec<bitterness::IbuReport> {
+        [
+            bitterness::Model::Boil(ibu::Method::Tinseth(ibu::Tinseth {})),
+            bitterness::Model::Boil(ibu::Method::Rager),
+            bitterness::Model::Boil(ibu::Method::Garetz),
+            bitterness::Model::MIbu(hop_stand),
+            bitterness::Model::Smph(hop_stand),
+        ]
+        .iter()
+        .map(|model| self.ibu_report(model))
+        .collect()
+    }
+
+    fn hop_contribution(
+        &self,
+        hop: &Hop,
+        model: &bitterness::Model,
+    ) -> bitterness::HopContribution {
+        let mut aged;
+        let hop = match &self.hop_storage {
+            Some(storage) => {
+                aged = hop.clone();
+                aged.alpha = bitterness::aged_alpha(hop, storage);
+                &aged
+            }
+            None => hop,
+        };
+        let (volume, gravity) = if hop.bittering() {
+            let time = bitterness::time_in_boil(hop, self.boil.boil_time);
+            (
+                self.average_boil_volume(time),
+                self.average_specific_gravity(time),
+            )
+        } else {
+            // Aroma and dry hops are added to the wort or beer after the boil
+            (self.batch_size, self.og_or_estimated())
+        };
+        let ibu = model.ibu(hop, self.boil.boil_time, volume, gravity) * self.hop_utilization();
+        bitterness::HopContribution::new(hop, volume, gravity, ibu)
+    }
+
+    /// Large batch hop utilization factor
+    fn hop_utilization(&self) -> f32 {
+        self.equipment
+            .as_ref()
+            .and_then(|equipment| equipment.hop_utilization)
+            .map_or(1.0, |utilization| utilization / 100.0)
     }
 
     /// Average boil volume
@@ -100,11 +493,7 @@ impl<Src: RecipeSrc> Recipe<Src> {
     /// - $t$ \[min\]: `time`
     /// - $T$ \[min\]: End of boil
     fn average_specific_gravity(&self, time: Minutes) -> Liters {
-        let og = if let Some(og) = self.og {
-            og
-        } else {
-            self.estimated_og()
-        };
+        let og = self.og_or_estimated();
 
         let pre_boil_gravity = if let Some(pre_g) = self.pre_boil_gravity {
             pre_g
@@ -122,12 +511,138 @@ impl<Src: RecipeSrc> Recipe<Src> {
         (start_gravity + og) / 2.0
     }
 
+    /// Differences from this recipe to `other`
+    pub fn diff<T: RecipeSrc>(&self, other: &Recipe<T>) -> diff::RecipeDiff {
+        diff::RecipeDiff::new(self, other)
+    }
+
+    /// Pitching plans for all yeasts in the recipe
+    ///
+    /// - `age`: days since the yeast was manufactured
+    /// - `starter`: available starter setup, `None` if only direct pitching is considered
+    pub fn pitch_plans(
+        &self,
+        age: Days,
+        starter: Option<&pitch::StarterParams>,
+    ) -> Vec<pitch::PitchPlan> {
+        let og = self.og_or_estimated();
+        self.yeasts()
+            .map(|yeast| pitch::plan(yeast, og, self.batch_size, age, starter))
+            .collect()
+    }
+
+    fn og_or_estimated(&self) -> SpecificGravity {
+        if let Some(og) = self.og {
+            og
+        } else {
+            self.estimated_og()
+        }
+    }
+
+    /// Estimated pre-boil gravity
+    ///
+    /// The extract in the wort is conserved during the boil, so the gravity points scale with
+    /// the ratio of post- to pre-boil volume.
     pub fn estimated_pre_boil_gravity(&self) -> SpecificGravity {
-        todo!("Calculate pre-boil gravity from recipe");
+        let og = self.og_or_estimated();
+        1.0 + (og - 1.0) * self.batch_size / self.boil.pre_volume
     }
 
+    /// Estimated original gravity
+    ///
+    /// Sum of the gravity points the fermentables contribute to the batch volume.
     pub fn estimated_og(&self) -> SpecificGravity {
-        todo!("Calculate OG from recipe");
+        let points: f32 = self
+            .fermentables()
+            .map(|fermentable| self.extract_points(fermentable))
+            .sum();
+        1.0 + points / self.batch_size / 1000.0
+    }
+
+    /// Gravity points a fermentable contributes to one liter of wort
+    ///
+    /// The yield of grains and adjuncts is scaled by the brewhouse efficiency,
+    /// while sugars and extracts are fully dissolved.
+    pub fn extract_points(&self, fermentable: &Fermentable) -> f32 {
+        let efficiency = if fermentable.type_.mashed() {
+            self.efficiency / 100.0
+        } else {
+            1.0
+        };
+        potential_points(fermentable) * efficiency
+    }
+
+    /// Estimated color
+    ///
+    /// Morey's equation: $SRM = 1.4922 \cdot MCU^{0.6859}$, where the malt color units are
+    /// $MCU = \sum_i L_i m_i / V$, with color $L_i$ in °L, mass $m_i$ in lb and batch size $V$
+    /// in gal.
+    pub fn estimated_color(&self) -> SRMColor {
+        let mcu: f32 = self
+            .fermentables()
+            .map(|fermentable| {
+                fermentable.color * Mass::from_kilograms(fermentable.amount).pounds()
+            })
+            .sum::<f32>()
+            / self.batch_volume().gallons();
+        1.4922 * mcu.powf(0.6859)
+    }
+}
+
+/// Gravity points a fermentable contributes to one liter of wort at 100 % efficiency
+pub fn potential_points(fermentable: &Fermentable) -> f32 {
+    efficiency::potential_extract(fermentable) * SUCROSE_POTENTIAL
+}
+
+/// Recipe summary displayed in a unit system
+#[derive(Debug)]
+pub struct RecipeDisplay<'a, Src: RecipeSrc> {
+    recipe: &'a Recipe<Src>,
+    system: UnitSystem,
+}
+
+impl<Src: RecipeSrc> fmt::Display for RecipeDisplay<'_, Src> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (recipe, system) = (self.recipe, self.system);
+        writeln!(f, "{}", recipe.name)?;
+        writeln!(
+            f,
+            "Batch: {}, boil: {}, {} min",
+            recipe.batch_volume().display(system),
+            recipe.pre_boil_volume().display(system),
+            recipe.boil_time()
+        )?;
+        write!(f, "OG: {}", recipe.original_gravity().display(system))?;
+        if let Some(fg) = recipe.final_gravity() {
+            write!(f, ", FG: {}", fg.display(system))?;
+        }
+        writeln!(
+            f,
+            ", IBU: {:.1}, color: {}",
+            recipe.ibu(),
+            Color::from_srm(recipe.estimated_color()).display(system)
+        )?;
+        for fermentable in recipe.fermentables() {
+            writeln!(
+                f,
+                "  {} {}",
+                Mass::from_kilograms(fermentable.amount).display(system),
+                fermentable.name
+            )?;
+        }
+        for hop in recipe.hops() {
+            writeln!(
+                f,
+                "  {} {}, {} min",
+                Mass::from_kilograms(hop.amount).display(system),
+                hop.name,
+                hop.time
+            )?;
+        }
+        for yeast in recipe.yeasts() {
+            writeln!(f, "  {}", yeast.name)?;
+        }
+        Ok(())
     }
 }
 
@@ -0,0 +1,220 @@
+//! # Sparge
+//!
+//! Water volumes, runnings and lauter efficiency of fly, batch and no-sparge lautering.
+//!
+//! The converted extract is dissolved in the mash water. Draining the mash leaves the wort
+//! absorbed by the grain and the lauter tun's deadspace behind, which sparging rinses out:
+//!
+//! - Batch sparge: the retained wort is mixed with each batch of sparge water and drained again
+//! - Fly sparge: the grain bed is rinsed as a well-mixed volume, so the extract washes out
+//!   exponentially and every retained volume of sparge water reduces the extract left in
+//!   the bed by a factor $e$
+//! - No sparge: the full boil volume is mashed, as in brew-in-a-bag, and drained once
+//!
+//! The grain absorption and deadspace come from the recipe's [`System`](../system/struct.System.html).
+//! The runnings are collected to the pre-boil volume of the recipe.
+//! Volumes of the dissolved extract are ignored.
+use crate::bryggio::efficiency;
+use crate::bryggio::mash::GRAIN_VOLUME;
+use crate::bryggio::quantity::Gravity;
+use crate::bryggio::recipe::{Recipe, RecipeSrc};
+use crate::bryggio::system::System;
+use brew_calculator::units::*;
+use serde::Serialize;
+use thiserror::Error;
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+pub enum Method {
+    Fly,
+    Batch,
+    /// Full volume mash, e.g. brew-in-a-bag
+    NoSparge,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+pub struct SpargeParams {
+    /// Water to grain ratio of the mash, l/kg, used when the mash steps have no infusions
+    ///
+    /// The malt pipe deadspace of an all-in-one system is added to the mash water.
+    pub mash_thickness: f32,
+    /// Share of the potential extract dissolved in the mash
+    pub conversion: Percent,
+}
+
+impl Default for SpargeParams {
+    fn default() -> Self {
+        Self {
+            mash_thickness: 3.0,
+            conversion: 95.0,
+        }
+    }
+}
+
+/// Wort collected in one draining of the mash
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct Runnings {
+    pub name: String,
+    /// Sparge water added before draining
+    pub sparge_water: Liters,
+    pub volume: Liters,
+    pub gravity: SpecificGravity,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Error)]
+pub enum SpargeError {
+    #[error("Recipe has no mashed fermentables to lauter")]
+    NoGrain,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct SpargePlan {
+    pub method: Method,
+    pub mash_water: Liters,
+    pub sparge_water: Liters,
+    pub sparge_temp: Option<Celsius>,
+    /// Wort left in the grain and the lauter tun
+    pub retained: Liters,
+    pub runnings: Vec<Runnings>,
+    pub pre_boil_volume: Liters,
+    pub pre_boil_gravity: SpecificGravity,
+    /// Share of the dissolved extract collected in the boil kettle
+    pub lauter_efficiency: Percent,
+}
+
+impl SpargePlan {
+    pub fn new<Src: RecipeSrc>(
+        recipe: &Recipe<Src>,
+        method: Method,
+        params: &SpargeParams,
+    ) -> Result<Self, SpargeError> {
+        let grain = recipe.grain_mass();
+        let extract: Kilograms = recipe
+            .fermentables()
+            .filter(|fermentable| fermentable.type_.mashed())
+            .map(efficiency::potential_extract)
+            .sum::<Kilograms>()
+            * params.conversion
+            / 100.0;
+        let system = recipe.system();
+        let retained = system.retained(grain);
+        let pre_boil_volume = recipe.pre_boil_volume().liters();
+        let infused: Liters = recipe
+            .mash_steps()
+            .filter_map(|step| step.infuse_amount)
+            .sum();
+        let mash_water = match method {
+            Method::NoSparge => system.full_volume(pre_boil_volume, grain),
+            _ if infused > 0.0 => infused,
+            _ => grain * params.mash_thickness + system.malt_pipe_deadspace,
+        };
+        if extract <= 0.0 || mash_water <= 0.0 {
+            return Err(SpargeError::NoGrain);
+        }
+        let first_runnings = (mash_water - retained).max(0.0);
+        let sparge_water = match method {
+            Method::NoSparge => 0.0,
+            _ => (pre_boil_volume - first_runnings).max(0.0),
+        };
+
+        let mut runnings = vec![Runnings::new(
+            "First runnings".into(),
+            0.0,
+            first_runnings,
+            extract * first_runnings / mash_water,
+        )];
+        // Extract in the retained wort, all of it if the grain absorbs the whole mash water
+        let mut left = extract * (1.0 - first_runnings / mash_water);
+        match method {
+            Method::Batch if sparge_water > 0.0 => {
+                let batches = batches(&system, grain, retained, sparge_water);
+                for batch in 1..=batches {
+                    let water = sparge_water / batches as f32;
+                    let collected = left * water / (retained + water);
+                    left -= collected;
+                    runnings.push(Runnings::new(
+                        format!("Batch sparge {}", batch),
+                        water,
+                        water,
+                        collected,
+                    ));
+                }
+            }
+            Method::Fly if sparge_water > 0.0 => {
+                let collected = left * (1.0 - (-sparge_water / retained).exp());
+                left -= collected;
+                runnings.push(Runnings::new(
+                    "Fly sparge".into(),
+                    sparge_water,
+                    sparge_water,
+                    collected,
+                ));
+            }
+            _ => {}
+        }
+        let volume: Liters = runnings.iter().map(|runnings| runnings.volume).sum();
+        Ok(Self {
+            method,
+            mash_water,
+            sparge_water,
+            sparge_temp: recipe.mash.sparge_temp,
+            retained,
+            runnings,
+            pre_boil_volume: volume,
+            pre_boil_gravity: gravity(extract - left, volume),
+            lauter_efficiency: 100.0 * (extract - left) / extract,
+        })
+    }
+}
+
+impl Runnings {
+    fn new(name: String, sparge_water: Liters, volume: Liters, extract: Kilograms) -> Self {
+        Self {
+            name,
+            sparge_water,
+            volume,
+            gravity: gravity(extract, volume),
+        }
+    }
+}
+
+/// Plans for all methods, for comparing their lauter efficiencies
+pub fn compare<Src: RecipeSrc>(
+    recipe: &Recipe<Src>,
+    params: &SpargeParams,
+) -> Result<Vec<SpargePlan>, SpargeError> {
+    [Method::Fly, Method::Batch, Method::NoSparge]
+        .iter()
+        .map(|method| SpargePlan::new(recipe, *method, params))
+        .collect()
+}
+
+/// Equal batches of sparge water, as many as needed to fit the mash tun
+fn batches(system: &System, grain: Kilograms, retained: Liters, sparge_water: Liters) -> usize {
+    let capacity = system
+        .mash_volume
+        .map(|mash_volume| mash_volume - grain * GRAIN_VOLUME - retained);
+    match capacity {
+        Some(capacity) if capacity > 0.0 => (sparge_water / capacity).ceil().max(1.0) as usize,
+        _ => 1,
+    }
+}
+
+/// Gravity of `extract` kg dissolved in `water` l
+fn gravity(extract: Kilograms, water: Liters) -> SpecificGravity {
+    if water <= 0.0 {
+        return 1.0;
+    }
+    Gravity::from_plato(100.0 * extract / (extract + water)).specific_gravity()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gravity_of_runnings() {
+        // 1 kg of extract in 8 l is 11.1 °P
+        assert!((gravity(1.0, 8.0) - 1.0448).abs() < 1e-3);
+        assert_eq!(gravity(0.0, 0.0), 1.0);
+    }
+}
@@ -0,0 +1,339 @@
+//! # Substitution
+//!
+//! Replacements from a library for an ingredient of a recipe that is not at hand.
+//!
+//! Candidates are ranked by their similarity to the replaced ingredient:
+//!
+//! - Hops by alpha acids and oil profile, and preferred when listed as substitutes
+//! - Fermentables of the same type by color and yield
+//! - Yeasts of the same type by attenuation, flocculation and temperature range
+//!
+//! The amounts of the replacements are adjusted to keep the recipe's bitterness,
+//! gravity or color.
+//! A specialty malt replaced for its color changes the gravity,
+//! which [`Recipe::substitute_fermentable`] makes up with the base malt.
+use crate::bryggio::library::Library;
+use crate::bryggio::recipe::{Recipe, RecipeSrc};
+use crate::bryggio::{bitterness, efficiency};
+use crate::bryggio::{Fermentable, Hop, Yeast};
+use beerxml::yeast::Flocculation;
+use brew_calculator::units::*;
+
+/// Fermentables at least this dark, in °L, are used for color rather than extract
+const SPECIALTY_COLOR: f32 = 20.0;
+
+/// Replacement for all additions of an ingredient
+#[derive(Clone, Debug, PartialEq)]
+pub struct Substitution<T> {
+    /// Name of the replaced ingredient
+    pub replaces: String,
+    /// Name of the replacement
+    pub name: String,
+    /// Similarity to the replaced ingredient, from 0 to 1
+    pub similarity: f32,
+    /// Replacement of each addition, in recipe order, with the amount adjusted
+    pub additions: Vec<T>,
+}
+
+/// Hops from `library` that can replace the hop named `name` in `recipe`, best first
+///
+/// Boil additions are scaled by the ratio of the alpha acids and form utilization
+/// to keep the IBU,
+/// aroma and dry hop additions keep their amount.
+pub fn hops<Src: RecipeSrc>(
+    recipe: &Recipe<Src>,
+    name: &str,
+    library: &Library,
+) -> Vec<Substitution<Hop>> {
+    let additions: Vec<&Hop> = recipe
+        .hops()
+        .filter(|hop| hop.name.eq_ignore_ascii_case(name))
+        .collect();
+    let original = match additions.first() {
+        Some(original) => *original,
+        None => return Vec::new(),
+    };
+    let candidates = library
+        .hops
+        .iter()
+        .filter(|candidate| !candidate.name.eq_ignore_ascii_case(name) && candidate.alpha > 0.0)
+        .map(|candidate| {
+            let factor = original.alpha * bitterness::form_factor(original.form)
+                / (candidate.alpha * bitterness::form_factor(candidate.form));
+            let additions = additions
+                .iter()
+                .map(|addition| {
+                    let mut hop = candidate.clone();
+                    hop.use_ = addition.use_;
+                    hop.time = addition.time;
+                    hop.amount = if addition.bittering() {
+                        addition.amount * factor
+                    } else {
+                        addition.amount
+                    };
+                    hop
+                })
+                .collect();
+            Substitution {
+                replaces: original.name.clone(),
+                name: candidate.name.clone(),
+                similarity: hop_similarity(original, candidate),
+                additions,
+            }
+        });
+    ranked(candidates)
+}
+
+/// Fermentables of the same type from `library` that can replace the fermentable named `name`
+/// in `recipe`, best first
+///
+/// Base malts, sugars and extracts are scaled to keep the gravity,
+/// specialty malts from 20 °L to keep the color.
+/// The gravity lost or gained by a specialty malt is made up with the base malt
+/// once the substitution is applied, see [`rebalance_base_malt`].
+pub fn fermentables<Src: RecipeSrc>(
+    recipe: &Recipe<Src>,
+    name: &str,
+    library: &Library,
+) -> Vec<Substitution<Fermentable>> {
+    let additions: Vec<&Fermentable> = recipe
+        .fermentables()
+        .filter(|fermentable| fermentable.name.eq_ignore_ascii_case(name))
+        .collect();
+    let original = match additions.first() {
+        Some(original) => *original,
+        None => return Vec::new(),
+    };
+    let candidates = library
+        .fermentables
+        .iter()
+        .filter(|candidate| {
+            !candidate.name.eq_ignore_ascii_case(name)
+                && candidate.type_ == original.type_
+                && candidate.color > 0.0
+                && candidate.yield_ > 0.0
+        })
+        .map(|candidate| {
+            let factor = if original.type_.mashed() && original.color >= SPECIALTY_COLOR {
+                original.color / candidate.color
+            } else {
+                extract_per_kg(original) / extract_per_kg(candidate)
+            };
+            let additions = additions
+                .iter()
+                .map(|addition| {
+                    let mut fermentable = candidate.clone();
+                    fermentable.amount = addition.amount * factor;
+                    fermentable.add_after_boil = addition.add_after_boil;
+                    fermentable
+                })
+                .collect();
+            Substitution {
+                replaces: original.name.clone(),
+                name: candidate.name.clone(),
+                similarity: fermentable_similarity(original, candidate),
+                additions,
+            }
+        });
+    ranked(candidates)
+}
+
+/// Yeasts of the same type from `library` that can replace the yeast named `name` in `recipe`,
+/// best first
+///
+/// The amounts are kept.
+pub fn yeasts<Src: RecipeSrc>(
+    recipe: &Recipe<Src>,
+    name: &str,
+    library: &Library,
+) -> Vec<Substitution<Yeast>> {
+    let additions: Vec<&Yeast> = recipe
+        .yeasts()
+        .filter(|yeast| yeast.name.eq_ignore_ascii_case(name))
+        .collect();
+    let original = match additions.first() {
+        Some(original) => *original,
+        None => return Vec::new(),
+    };
+    let candidates = library
+        .yeasts
+        .iter()
+        .filter(|candidate| {
+            !candidate.name.eq_ignore_ascii_case(name) && candidate.type_ == original.type_
+        })
+        .map(|candidate| {
+            let additions = additions
+                .iter()
+                .map(|addition| {
+                    let mut yeast = candidate.clone();
+                    yeast.amount = addition.amount;
+                    yeast.amount_is_weight = addition.amount_is_weight;
+                    yeast
+                })
+                .collect();
+            Substitution {
+                replaces: original.name.clone(),
+                name: candidate.name.clone(),
+                similarity: yeast_similarity(original, candidate),
+                additions,
+            }
+        });
+    ranked(candidates)
+}
+
+/// Replaces the additions of the substituted ingredient in `ingredients`, in order
+pub(crate) fn apply<T>(ingredients: &mut [T], name: fn(&T) -> &str, substitution: Substitution<T>) {
+    let mut additions = substitution.additions.into_iter();
+    for ingredient in ingredients.iter_mut() {
+        if name(ingredient).eq_ignore_ascii_case(&substitution.replaces) {
+            if let Some(addition) = additions.next() {
+                *ingredient = addition;
+            }
+        }
+    }
+}
+
+/// Scale the base malt of `recipe` to bring its gravity points back to `points`
+///
+/// The base malt is the largest mashed fermentable below 20 °L other than `replacement`.
+/// Its color is low enough to leave the color of the recipe about unchanged.
+/// A recipe without a base malt keeps its gravity change.
+pub(crate) fn rebalance_base_malt<Src: RecipeSrc>(
+    recipe: &mut Recipe<Src>,
+    points: f32,
+    replacement: &str,
+) {
+    let missing = points
+        - recipe
+            .fermentables()
+            .map(|fermentable| recipe.extract_points(fermentable))
+            .sum::<f32>();
+    let base = recipe
+        .fermentables()
+        .enumerate()
+        .filter(|(_, fermentable)| {
+            fermentable.type_.mashed()
+                && fermentable.color < SPECIALTY_COLOR
+                && fermentable.amount > 0.0
+                && !fermentable.name.eq_ignore_ascii_case(replacement)
+        })
+        .max_by(|(_, a), (_, b)| a.amount.partial_cmp(&b.amount).unwrap())
+        .map(|(idx, fermentable)| (idx, recipe.extract_points(fermentable) / fermentable.amount));
+    if let Some((idx, points_per_kg)) = base {
+        let base = &mut recipe.fermentables[idx];
+        base.amount = (base.amount + missing / points_per_kg).max(0.0);
+    }
+}
+
+fn ranked<T>(candidates: impl Iterator<Item = Substitution<T>>) -> Vec<Substitution<T>> {
+    let mut candidates: Vec<Substitution<T>> = candidates.collect();
+    candidates.sort_by(|a, b| b.similarity.partial_cmp(&a.similarity).unwrap());
+    candidates
+}
+
+/// Similarity from a distance, where 0 is identical
+fn similarity(distance: f32) -> f32 {
+    (-distance).exp()
+}
+
+/// Alpha acids within 20 % and oils within 10 percentage points are considered close.
+/// Listed substitutes are halfway to identical.
+fn hop_similarity(original: &Hop, candidate: &Hop) -> f32 {
+    let alpha = (original.alpha - candidate.alpha).abs() / original.alpha.max(candidate.alpha);
+    let oils = [
+        (original.myrcene, candidate.myrcene),
+        (original.humulene, candidate.humulene),
+        (original.caryophyllene, candidate.caryophyllene),
+        (original.cohumulone, candidate.cohumulone),
+    ];
+    let oil_distances: Vec<f32> = oils
+        .iter()
+        .filter_map(|(a, b)| Some((a.as_ref()? - b.as_ref()?).abs() / 10.0))
+        .collect();
+    let oils = if oil_distances.is_empty() {
+        0.0
+    } else {
+        oil_distances.iter().sum::<f32>() / oil_distances.len() as f32
+    };
+    let similarity = similarity(alpha / 0.2 + oils);
+    if lists_substitute(original, candidate) || lists_substitute(candidate, original) {
+        (1.0 + similarity) / 2.0
+    } else {
+        similarity
+    }
+}
+
+/// True if `candidate` is among the free text substitutes of `hop`
+fn lists_substitute(hop: &Hop, candidate: &Hop) -> bool {
+    hop.substitutes.as_ref().is_some_and(|substitutes| {
+        substitutes
+            .split([',', ';', '/'])
+            .flat_map(|name| name.split(" and "))
+            .any(|name| name.trim().eq_ignore_ascii_case(&candidate.name))
+    })
+}
+
+/// Colors are compared on a log scale, where doubling the color is a distance of 1.
+/// A yield 5 percentage points apart is a distance of 1.
+fn fermentable_similarity(original: &Fermentable, candidate: &Fermentable) -> f32 {
+    let color = ((original.color + 1.0) / (candidate.color + 1.0))
+        .log2()
+        .abs();
+    let yield_ = (original.yield_ - candidate.yield_).abs() / 5.0;
+    similarity(color + yield_)
+}
+
+/// Attenuation 5 percentage points apart, one step of flocculation and
+/// a temperature range 5 °C apart are each a distance of 1.
+fn yeast_similarity(original: &Yeast, candidate: &Yeast) -> f32 {
+    let attenuation = original
+        .attenuation
+        .zip(candidate.attenuation)
+        .map_or(0.0, |(a, b)| (a - b).abs() / 5.0);
+    let flocculation = original
+        .flocculation
+        .zip(candidate.flocculation)
+        .map_or(0.0, |(a, b)| {
+            (flocculation_level(a) - flocculation_level(b)).abs()
+        });
+    let temp =
+        |a: Option<Celsius>, b: Option<Celsius>| a.zip(b).map_or(0.0, |(a, b)| (a - b).abs());
+    let temperature = (temp(original.min_temperature, candidate.min_temperature)
+        + temp(original.max_temperature, candidate.max_temperature))
+        / 2.0
+        / 5.0;
+    similarity(attenuation + flocculation + temperature)
+}
+
+fn flocculation_level(flocculation: Flocculation) -> f32 {
+    match flocculation {
+        Flocculation::Low => 0.0,
+        Flocculation::Medium => 1.0,
+        Flocculation::High => 2.0,
+        Flocculation::VeryHigh => 3.0,
+    }
+}
+
+fn extract_per_kg(fermentable: &Fermentable) -> Kilograms {
+    let mut kilogram = fermentable.clone();
+    kilogram.amount = 1.0;
+    efficiency::potential_extract(&kilogram)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use beerxml::hop::Use;
+
+    #[test]
+    fn listed_substitutes() {
+        let mut cascade = Hop::new("Cascade".into(), 5.5, 0.03, Use::Boil, 60.0);
+        cascade.substitutes = Some("Centennial; Amarillo and Ahtanum".into());
+        let amarillo = Hop::new("Amarillo".into(), 9.0, 0.0, Use::Boil, 0.0);
+        let galena = Hop::new("Galena".into(), 13.0, 0.0, Use::Boil, 0.0);
+        assert!(lists_substitute(&cascade, &amarillo));
+        assert!(!lists_substitute(&cascade, &galena));
+        assert!(hop_similarity(&cascade, &amarillo) > 0.5);
+        assert!(hop_similarity(&cascade, &galena) < 0.1);
+    }
+}
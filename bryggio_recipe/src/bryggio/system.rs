@@ -0,0 +1,127 @@
+//! # Brewing systems
+//!
+//! Volumes of the brewing system that the water and lauter calculations depend on.
+//!
+//! - Traditional: mash/lauter tun and kettle, the wort in the tun's deadspace is lost
+//! - Brew-in-a-bag: the full volume is mashed in the kettle and the bag is squeezed,
+//!   so the spent grain keeps less wort
+//! - All-in-one: mashed in a malt pipe inside the kettle, like the Grainfather,
+//!   Brewzilla and Braumeister. The water below the malt pipe does not wet the grain,
+//!   but drains into the boil with the wort.
+//!
+//! The presets are typical values, a measured absorption and deadspace should replace them.
+use crate::bryggio::sparge::Method;
+use crate::bryggio::Equipment;
+use brew_calculator::units::*;
+use serde::Serialize;
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+pub enum Kind {
+    Traditional,
+    BrewInABag,
+    AllInOne,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+pub struct System {
+    pub kind: Kind,
+    /// Wort retained by the spent grain, l/kg, after squeezing for brew-in-a-bag
+    pub grain_absorption: f32,
+    /// Wort left in the lauter tun, lost with the spent grain
+    pub lauter_deadspace: Liters,
+    /// Water below the malt pipe, added to the mash water but collected with the wort
+    pub malt_pipe_deadspace: Liters,
+    /// How the grain is rinsed, `NoSparge` for a full-volume mash
+    pub sparge: Method,
+    /// Volume of the vessel holding the mash, limiting the size of batch sparges
+    pub mash_volume: Option<Liters>,
+}
+
+impl Default for System {
+    fn default() -> Self {
+        Self::traditional()
+    }
+}
+
+impl System {
+    /// Mash/lauter tun with a false bottom, batch sparged
+    pub fn traditional() -> Self {
+        Self {
+            kind: Kind::Traditional,
+            grain_absorption: 1.0,
+            lauter_deadspace: 0.0,
+            malt_pipe_deadspace: 0.0,
+            sparge: Method::Batch,
+            mash_volume: None,
+        }
+    }
+
+    /// Traditional system with the tun volume and lauter deadspace of `equipment`
+    pub fn from_equipment(equipment: &Equipment) -> Self {
+        Self {
+            lauter_deadspace: equipment.lauter_deadspace.unwrap_or(0.0),
+            mash_volume: equipment.tun_volume,
+            ..Self::traditional()
+        }
+    }
+
+    /// Full-volume mash in a bag, squeezed after mashing
+    pub fn brew_in_a_bag() -> Self {
+        Self {
+            kind: Kind::BrewInABag,
+            grain_absorption: 0.5,
+            sparge: Method::NoSparge,
+            ..Self::traditional()
+        }
+    }
+
+    /// Grainfather G30, fly sparged through the malt pipe
+    pub fn grainfather() -> Self {
+        Self::all_in_one(3.5, Method::Fly, 30.0)
+    }
+
+    /// Brewzilla 35 l, mashed at full volume
+    pub fn brewzilla() -> Self {
+        Self::all_in_one(5.0, Method::NoSparge, 35.0)
+    }
+
+    /// Braumeister 20 l, mashed at full volume
+    pub fn braumeister() -> Self {
+        Self::all_in_one(6.0, Method::NoSparge, 30.0)
+    }
+
+    fn all_in_one(malt_pipe_deadspace: Liters, sparge: Method, mash_volume: Liters) -> Self {
+        Self {
+            kind: Kind::AllInOne,
+            grain_absorption: 0.8,
+            malt_pipe_deadspace,
+            sparge,
+            mash_volume: Some(mash_volume),
+            ..Self::traditional()
+        }
+    }
+
+    /// Wort left behind in the grain and the lauter tun after draining `grain` kg
+    pub fn retained(&self, grain: Kilograms) -> Liters {
+        grain * self.grain_absorption + self.lauter_deadspace
+    }
+
+    /// Mash water of a full-volume mash collecting `pre_boil_volume`
+    pub fn full_volume(&self, pre_boil_volume: Liters, grain: Kilograms) -> Liters {
+        pre_boil_volume + self.retained(grain)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn squeezed_bag_retains_less() {
+        let grain = 5.0;
+        assert_eq!(System::traditional().full_volume(25.0, grain), 30.0);
+        assert_eq!(System::brew_in_a_bag().full_volume(25.0, grain), 27.5);
+        // The malt pipe deadspace is collected with the wort
+        assert_eq!(System::braumeister().retained(grain), 4.0);
+    }
+}
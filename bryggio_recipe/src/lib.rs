@@ -38,5 +38,10 @@
 #![cfg_attr(all(not(test), feature = "clippy"), warn(result_unwrap_used))]
 #![cfg_attr(feature = "clippy", warn(unseparated_literal_suffix))]
 #![cfg_attr(feature = "clippy", warn(wrong_pub_self_convention))]
+pub mod beerjson_conv;
 pub mod beerxml_conv;
+pub mod brewfather_conv;
 pub mod bryggio;
+pub mod load;
+
+pub use load::load;
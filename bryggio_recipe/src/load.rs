@@ -0,0 +1,232 @@
+//! # Loading recipes
+//!
+//! Reads recipes from any of the supported formats,
+//! detecting the format from the contents:
+//!
+//! - BeerXML, a single `<RECIPE>` or a `<RECIPES>` list
+//! - BeerJSON
+//! - Brewfather JSON, a single recipe or a list of recipes
+use crate::beerjson_conv::{BeerJson, BeerJsonSrc};
+use crate::beerxml_conv::BeerXmlSrc;
+use crate::brewfather_conv::{BrewfatherRecipe, BrewfatherSrc};
+use crate::bryggio::{recipe::RecipeSrc, Recipe};
+use beerxml::encoding;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+use thiserror::Error;
+
+/// Format of a recipe file
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Format {
+    /// A single BeerXML `<RECIPE>`
+    BeerXml,
+    /// A BeerXML `<RECIPES>` list
+    BeerXmlList,
+    BeerJson,
+    Brewfather,
+}
+
+/// Source of a loaded recipe, and its original values
+#[derive(Debug, Clone)]
+pub enum RecipeSource {
+    BeerXml(BeerXmlSrc),
+    BeerJson(BeerJsonSrc),
+    Brewfather(BrewfatherSrc),
+}
+
+impl RecipeSrc for RecipeSource {}
+
+#[derive(Debug, Error)]
+pub enum LoadError {
+    #[error("Could not read '{0}': {1}")]
+    Read(String, io::Error),
+    #[error("Could not decode recipe: {0}")]
+    Decode(#[from] encoding::Error),
+    #[error("Unknown recipe format")]
+    UnknownFormat,
+    #[error("Invalid {0:?} recipe: {1}")]
+    Xml(Format, serde_xml_rs::Error),
+    #[error("Invalid {0:?} recipe: {1}")]
+    Json(Format, serde_json::Error),
+}
+
+/// Reads all recipes in the file at `path`
+pub fn load<P: AsRef<Path>>(path: P) -> Result<Vec<Recipe<RecipeSource>>, LoadError> {
+    let path = path.as_ref();
+    let bytes = fs::read(path).map_err(|err| LoadError::Read(path.display().to_string(), err))?;
+    from_bytes(&bytes)
+}
+
+/// A recipe file, or directory, that could not be loaded
+#[derive(Debug, Clone, Serialize)]
+pub struct Failure {
+    pub path: String,
+    pub error: String,
+}
+
+impl Failure {
+    fn new(path: &Path, error: impl ToString) -> Self {
+        Self {
+            path: path.display().to_string(),
+            error: error.to_string(),
+        }
+    }
+}
+
+/// Reads all recipes in the `.xml` and `.json` files in `dir` and its subdirectories
+///
+/// Files are read in sorted order. Files, entries and subdirectories that can not be
+/// read or parsed are returned as failures, only an unreadable `dir` is an error.
+pub fn load_dir<P: AsRef<Path>>(dir: P) -> io::Result<(Vec<Recipe<RecipeSource>>, Vec<Failure>)> {
+    let mut files = Vec::new();
+    let mut failures = Vec::new();
+    list_recipes(dir.as_ref(), &mut files, &mut failures)?;
+    files.sort();
+    let mut recipes = Vec::new();
+    for file in files {
+        match load(&file) {
+            Ok(loaded) => recipes.extend(loaded),
+            Err(error) => failures.push(Failure::new(&file, error)),
+        }
+    }
+    Ok((recipes, failures))
+}
+
+fn list_recipes(
+    dir: &Path,
+    files: &mut Vec<PathBuf>,
+    failures: &mut Vec<Failure>,
+) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = match entry {
+            Ok(entry) => entry.path(),
+            Err(error) => {
+                failures.push(Failure::new(dir, error));
+                continue;
+            }
+        };
+        if path.is_dir() {
+            if let Err(error) = list_recipes(&path, files, failures) {
+                failures.push(Failure::new(&path, error));
+            }
+        } else if is_recipe_file(&path) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn is_recipe_file(path: &Path) -> bool {
+    match path.extension() {
+        Some(ext) => ext.eq_ignore_ascii_case("xml") || ext.eq_ignore_ascii_case("json"),
+        None => false,
+    }
+}
+
+/// Reads all recipes in `bytes`
+///
+/// The encoding is given by the byte order mark or the XML declaration,
+/// see [`beerxml::encoding`](../../beerxml/encoding/index.html).
+pub fn from_bytes(bytes: &[u8]) -> Result<Vec<Recipe<RecipeSource>>, LoadError> {
+    from_str(&encoding::decode(bytes)?)
+}
+
+/// Reads all recipes in `contents`
+pub fn from_str(contents: &str) -> Result<Vec<Recipe<RecipeSource>>, LoadError> {
+    let contents = contents.trim_start_matches('\u{feff}').trim_start();
+    let format = detect(contents).ok_or(LoadError::UnknownFormat)?;
+    let beerxml = |recipe: beerxml::Recipe| Recipe::from(recipe).map_src(RecipeSource::BeerXml);
+    let brewfather =
+        |recipe: BrewfatherRecipe| Recipe::from(recipe).map_src(RecipeSource::Brewfather);
+    match format {
+        Format::BeerXml => serde_xml_rs::from_str::<beerxml::Recipe>(contents)
+            .map(|recipe| vec![beerxml(recipe)])
+            .map_err(|err| LoadError::Xml(format, err)),
+        Format::BeerXmlList => serde_xml_rs::from_str::<beerxml::Recipes>(contents)
+            .map(|recipes| recipes.recipe.into_iter().map(beerxml).collect())
+            .map_err(|err| LoadError::Xml(format, err)),
+        Format::BeerJson => serde_json::from_str::<BeerJson>(contents)
+            .map(|doc| {
+                doc.beerjson
+                    .into_recipes()
+                    .into_iter()
+                    .map(|recipe| recipe.map_src(RecipeSource::BeerJson))
+                    .collect()
+            })
+            .map_err(|err| LoadError::Json(format, err)),
+        Format::Brewfather => serde_json::from_str::<OneOrMany<BrewfatherRecipe>>(contents)
+            .map(|recipes| match recipes {
+                OneOrMany::One(recipe) => vec![brewfather(recipe)],
+                OneOrMany::Many(recipes) => recipes.into_iter().map(brewfather).collect(),
+            })
+            .map_err(|err| LoadError::Json(format, err)),
+    }
+}
+
+/// Format of `contents`, from the first XML element or JSON key
+pub fn detect(contents: &str) -> Option<Format> {
+    let contents = contents.trim_start_matches('\u{feff}').trim_start();
+    if contents.starts_with('<') {
+        match first_element(contents)?.to_ascii_uppercase().as_str() {
+            "RECIPE" => Some(Format::BeerXml),
+            "RECIPES" => Some(Format::BeerXmlList),
+            _ => None,
+        }
+    } else if contents.starts_with('{') || contents.starts_with('[') {
+        let json: serde_json::Value = serde_json::from_str(contents).ok()?;
+        if json.get("beerjson").is_some() {
+            Some(Format::BeerJson)
+        } else {
+            Some(Format::Brewfather)
+        }
+    } else {
+        None
+    }
+}
+
+/// Name of the first element, skipping declarations and comments
+fn first_element(mut xml: &str) -> Option<&str> {
+    loop {
+        xml = xml.trim_start();
+        if xml.starts_with("<?") {
+            xml = &xml[xml.find("?>")? + 2..];
+        } else if xml.starts_with("<!--") {
+            xml = &xml[xml.find("-->")? + 3..];
+        } else if xml.starts_with("<!") {
+            xml = &xml[xml.find('>')? + 1..];
+        } else {
+            let name = xml.strip_prefix('<')?;
+            let end = name
+                .find(|c: char| c.is_whitespace() || c == '>' || c == '/')
+                .unwrap_or(name.len());
+            return Some(&name[..end]);
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum OneOrMany<T> {
+    One(T),
+    Many(Vec<T>),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect_format() {
+        let xml = "<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?>\n<!-- list -->\n<RECIPES>";
+        assert_eq!(detect(xml), Some(Format::BeerXmlList));
+        assert_eq!(detect("<RECIPE><NAME>"), Some(Format::BeerXml));
+        assert_eq!(
+            detect(r#"{"beerjson": {"version": 1.0}}"#),
+            Some(Format::BeerJson)
+        );
+        assert_eq!(detect(r#"{"name": "Goedar"}"#), Some(Format::Brewfather));
+        assert_eq!(detect("<HOPS>"), None);
+        assert_eq!(detect("name,og"), None);
+    }
+}
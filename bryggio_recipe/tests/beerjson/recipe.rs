@@ -0,0 +1,89 @@
+use beer_recipe::beerjson_conv::{BeerJson, BeerJsonSrc};
+use beer_recipe::bryggio::Recipe;
+use beerxml::fermentable::Type;
+use beerxml::hop::{Form, Use};
+
+const DRY_STOUT: &str = "tests/beerjson/dry_stout.json";
+
+fn convert(contents: &str) -> Recipe<BeerJsonSrc> {
+    let mut doc: BeerJson = serde_json::from_str(contents).unwrap();
+    doc.beerjson.recipes.remove(0).into()
+}
+
+fn close(a: f32, b: f32) -> bool {
+    (a - b).abs() < 1e-3
+}
+
+#[test]
+fn units_are_converted() {
+    let recipe = convert(&std::fs::read_to_string(DRY_STOUT).unwrap());
+    assert_eq!(recipe.name, "Dry Stout");
+    assert!(close(recipe.batch_size, 18.927));
+    assert!(close(recipe.pre_boil_volume().liters(), 20.820));
+    assert_eq!(recipe.boil_time(), 60.0);
+    assert_eq!(recipe.efficiency(), 72.0);
+    assert!(close(recipe.original_gravity().specific_gravity(), 1.036));
+    assert!(close(
+        recipe.final_gravity().unwrap().specific_gravity(),
+        1.012
+    ));
+
+    let fermentables: Vec<_> = recipe.fermentables().collect();
+    assert_eq!(fermentables.len(), 4);
+    assert!(close(fermentables[0].amount, 2.268));
+    assert_eq!(fermentables[0].yield_, 78.0);
+    assert_eq!(fermentables[0].color, 3.0);
+    // Potential of 32 points out of sucrose's 46
+    assert!(close(fermentables[1].yield_, 69.565));
+    assert!((fermentables[1].color - 1.68).abs() < 0.01);
+    assert!(close(fermentables[2].amount, 0.4536));
+    assert!((fermentables[2].color - 500.0).abs() < 0.01);
+    assert_eq!(fermentables[3].type_, Type::Sugar);
+    assert!(close(fermentables[3].amount, 0.25));
+    assert_eq!(fermentables[3].add_after_boil, Some(true));
+    assert_eq!(fermentables[0].add_after_boil, None);
+
+    let hops: Vec<_> = recipe.hops().collect();
+    assert!(close(hops[0].amount, 0.0638));
+    assert_eq!(hops[0].alpha, 5.0);
+    assert_eq!((hops[0].use_, hops[0].time), (Use::Boil, 60.0));
+    assert_eq!(hops[0].form, Some(Form::Pellet));
+    assert!(close(hops[1].amount, 0.028));
+    assert_eq!((hops[1].use_, hops[1].time), (Use::DryHop, 4320.0));
+    assert_eq!(hops[1].form, Some(Form::Leaf));
+
+    let yeast = recipe.yeasts().next().unwrap();
+    assert!(close(yeast.amount, 0.125));
+    assert_eq!(yeast.amount_is_weight, Some(false));
+    assert_eq!(yeast.product_id.as_deref(), Some("WLP004"));
+
+    let misc = recipe.miscs().next().unwrap();
+    assert!(close(misc.amount, 0.005));
+    assert_eq!((misc.use_, misc.time), (beerxml::misc::Use::Boil, 10.0));
+
+    let steps: Vec<_> = recipe.mash_steps().collect();
+    assert_eq!(steps.len(), 2);
+    assert_eq!(steps[0].type_, beerxml::mash::Type::Infusion);
+    assert!((steps[0].infuse_amount.unwrap() - 9.937).abs() < 1e-3);
+    assert_eq!((steps[0].step_temp, steps[0].step_time), (68.0, 60.0));
+    assert_eq!(steps[0].ramp_time, Some(2.0));
+    assert_eq!(steps[1].name, "Mash Out");
+    assert_eq!(steps[1].type_, beerxml::mash::Type::Temperature);
+    assert!((steps[1].step_temp - 75.556).abs() < 1e-3);
+    assert_eq!(steps[1].infuse_amount, None);
+}
+
+#[test]
+fn missing_efficiency() {
+    let mut json: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(DRY_STOUT).unwrap()).unwrap();
+    json["beerjson"]["recipes"][0]
+        .as_object_mut()
+        .unwrap()
+        .remove("efficiency");
+    let recipe = convert(&json.to_string());
+    assert_eq!(
+        recipe.efficiency(),
+        beer_recipe::bryggio::recipe::DEFAULT_EFFICIENCY
+    );
+}
@@ -1 +1,2 @@
 mod hops;
+mod validation;
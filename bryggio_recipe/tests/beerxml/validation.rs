@@ -0,0 +1,78 @@
+use beerxml::validation::Severity;
+
+use crate::bryggio::beerxml_dry_stout as dry_stout;
+
+#[test]
+fn valid_recipe_has_only_warnings() {
+    let issues = dry_stout(&[]).validate();
+    assert!(issues
+        .iter()
+        .all(|issue| issue.severity == Severity::Warning));
+    assert!(issues.iter().any(|issue| issue.path == "hops.hop[0].form"));
+}
+
+#[test]
+fn impossible_values() {
+    let recipe = dry_stout(&[
+        ("<ALPHA>5.0</ALPHA>", "<ALPHA>0.0</ALPHA>"),
+        ("<TIME>60.0</TIME>", "<TIME>90.0</TIME>"),
+        ("<AMOUNT>2.27</AMOUNT>", "<AMOUNT>-2.27</AMOUNT>"),
+        ("<YIELD>70.0</YIELD>", "<YIELD>170.0</YIELD>"),
+    ]);
+    let issues = recipe.validate();
+    let find = |path: &str| {
+        issues
+            .iter()
+            .find(|issue| issue.path == path)
+            .unwrap_or_else(|| panic!("No issue for {}", path))
+    };
+    assert_eq!(find("hops.hop[0].alpha").severity, Severity::Error);
+    assert_eq!(find("hops.hop[0].time").severity, Severity::Warning);
+    assert_eq!(
+        find("fermentables.fermentable[0].amount").severity,
+        Severity::Error
+    );
+    assert_eq!(
+        find("fermentables.fermentable[1].yield_").severity,
+        Severity::Error
+    );
+    // Errors are listed first
+    assert_eq!(issues[0].severity, Severity::Error);
+    assert_eq!(issues.last().unwrap().severity, Severity::Warning);
+}
+
+#[test]
+fn mash_exceeds_tun_volume() {
+    let recipe = dry_stout(&[(
+        "<INFUSE_AMOUNT>10.0</INFUSE_AMOUNT>",
+        "<INFUSE_AMOUNT>30.0</INFUSE_AMOUNT>",
+    )]);
+    assert!(recipe
+        .validate()
+        .iter()
+        .all(|issue| !issue.path.starts_with("mash")));
+    // With an equipment of a 19 l tun
+    let recipe = dry_stout(&[
+        (
+            "<INFUSE_AMOUNT>10.0</INFUSE_AMOUNT>",
+            "<INFUSE_AMOUNT>30.0</INFUSE_AMOUNT>",
+        ),
+        (
+            "<HOPS>",
+            "<EQUIPMENT>
+                <NAME>Cooler</NAME>
+                <VERSION>1</VERSION>
+                <BOIL_SIZE>26.0</BOIL_SIZE>
+                <BATCH_SIZE>19.0</BATCH_SIZE>
+                <TUN_VOLUME>19.0</TUN_VOLUME>
+            </EQUIPMENT>
+            <HOPS>",
+        ),
+    ]);
+    let issues = recipe.validate();
+    let tun = issues
+        .iter()
+        .find(|issue| issue.path == "mash.mash_steps.mash_step[0].infuse_amount")
+        .unwrap();
+    assert!(tun.message.contains("tun volume"), "{}", tun);
+}
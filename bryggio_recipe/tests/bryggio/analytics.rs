@@ -0,0 +1,45 @@
+use beer_recipe::bryggio::analytics::RecipeStats;
+use beer_recipe::load;
+
+use super::dry_stout;
+
+#[test]
+fn hop_rates_per_recipe() {
+    // A second boil addition of 36.2 g, 100 g in total
+    let recipe = dry_stout(&[(
+        "</HOPS>",
+        "<HOP>
+            <NAME>Fuggles</NAME>
+            <VERSION>1</VERSION>
+            <ALPHA>4.5</ALPHA>
+            <AMOUNT>0.0362</AMOUNT>
+            <USE>Boil</USE>
+            <TIME>15.0</TIME>
+        </HOP></HOPS>",
+    )]);
+    let stats = RecipeStats::new(vec![&recipe, &dry_stout(&[])]);
+    assert_eq!(stats.recipes, 2);
+    let boil = &stats.hop_rates["Boil"];
+    assert_eq!(boil.count, 2);
+    assert!((boil.max - 100.0 / 18.93).abs() < 1e-3);
+    assert!((boil.min - 63.8 / 18.93).abs() < 1e-3);
+    assert_eq!(stats.hops.len(), 2);
+}
+
+#[test]
+fn unparsable_files_are_reported() {
+    // Besides the recipe, a hop list that is not a recipe
+    let (recipes, failures) = load::load_dir("tests/beerxml/data").unwrap();
+    assert_eq!(recipes.len(), 1);
+    assert_eq!(failures.len(), 1);
+    assert!(
+        failures[0].path.ends_with("hops.xml"),
+        "{}",
+        failures[0].path
+    );
+    assert_eq!(failures[0].error, "Unknown recipe format");
+    let stats = RecipeStats::new(&recipes);
+    assert_eq!(stats.recipes, 1);
+
+    assert!(load::load_dir("tests/no_such_dir").is_err());
+}
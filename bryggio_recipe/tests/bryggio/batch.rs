@@ -0,0 +1,58 @@
+use beer_recipe::beerxml_conv::BeerXmlSrc;
+use beer_recipe::bryggio::batch::{Batch, TemperatureReading};
+use beer_recipe::bryggio::Recipe;
+use beer_recipe::load;
+use beerxml::fermentable::Type;
+
+use super::beerxml_dry_stout;
+
+const GOEDAR_XML: &str = "tests/brewfather/Brewfather_BeerXML_Goedar_20200215.xml";
+
+#[test]
+fn batch_on_target_matches_recipe() {
+    let mut recipe = load::load(GOEDAR_XML).unwrap().remove(0);
+    recipe.set_og(recipe.estimated_og());
+    let mut batch = Batch::new(&recipe, Some("2020-02-15".into()));
+    batch.og = Some(recipe.estimated_og());
+    batch.batch_volume = Some(recipe.batch_size);
+    batch.fermentation_temps = vec![
+        TemperatureReading {
+            day: 0.0,
+            temp: 18.0,
+        },
+        TemperatureReading {
+            day: 3.0,
+            temp: 20.0,
+        },
+    ];
+    let report = batch.report(&recipe);
+    assert_eq!(batch.recipe, recipe.name);
+    assert!(report.og.unwrap().difference().abs() < 1e-6);
+    // The efficiency is measured on the extract by weight, the estimated OG on gravity points
+    assert!(report.efficiency.unwrap().difference().abs() < 0.1);
+    assert_eq!(report.fg, None);
+    assert_eq!(report.fermentation_temp.unwrap().mean, 19.0);
+
+    batch.fg = Some(recipe.estimated_fg());
+    let report = batch.report(&recipe);
+    assert!(report.abv.unwrap().difference().abs() < 1e-6);
+}
+
+#[test]
+fn extract_batch_has_no_efficiency() {
+    let mut recipe = beerxml_dry_stout(&[]);
+    for fermentable in &mut recipe.fermentables.fermentable {
+        fermentable.type_ = Type::DryExtract;
+    }
+    let recipe: Recipe<BeerXmlSrc> = recipe.into();
+    let batch = Batch {
+        pre_boil_gravity: Some(1.032),
+        pre_boil_volume: Some(20.8),
+        og: Some(1.036),
+        ..Batch::new(&recipe, None)
+    };
+    let report = batch.report(&recipe);
+    assert_eq!(report.efficiency, None);
+    assert_eq!(report.pre_boil_efficiency, None);
+    assert!(report.og.is_some());
+}
@@ -0,0 +1,78 @@
+use beer_recipe::bryggio::cost::{EnergyPrice, Price, PriceList};
+use beer_recipe::bryggio::ingredient::Kind;
+use beer_recipe::load;
+
+use super::dry_stout;
+
+const GOEDAR_JSON: &str = "tests/brewfather/Brewfather_RECIPE_Goedar_20200215.json";
+
+fn prices() -> PriceList {
+    let recipe = load::load(GOEDAR_JSON).unwrap().remove(0);
+    let mut prices = PriceList::new();
+    for fermentable in recipe.fermentables() {
+        prices.add(
+            Kind::Fermentable,
+            fermentable.name.clone(),
+            Price::PerKilogram(2.0),
+        );
+    }
+    for hop in recipe.hops() {
+        prices.add(Kind::Hop, hop.name.clone(), Price::PerKilogram(40.0));
+    }
+    for yeast in recipe.yeasts() {
+        let pack = Price::PerPack {
+            size: yeast.amount,
+            price: 5.0,
+        };
+        prices.add(Kind::Yeast, yeast.name.clone(), pack);
+    }
+    prices.water = Some(0.01);
+    prices.energy = Some(EnergyPrice::new(0.2, 2.5));
+    prices
+}
+
+#[test]
+fn breakdown_sums_to_total() {
+    let recipe = load::load(GOEDAR_JSON).unwrap().remove(0);
+    let cost = recipe.cost(&prices());
+    let grist: f32 = recipe.fermentables().map(|f| f.amount).sum();
+    assert!((cost.fermentables - 2.0 * grist).abs() < 1e-3);
+    assert_eq!(cost.yeasts, 5.0 * recipe.yeasts().count() as f32);
+    assert!(cost.water > 0.0 && cost.energy > 0.0);
+    let total = cost.fermentables + cost.hops + cost.yeasts + cost.miscs + cost.water + cost.energy;
+    assert!((cost.total - total).abs() < 1e-3);
+    assert!((cost.per_liter - cost.total / recipe.batch_size).abs() < 1e-5);
+    assert_eq!(cost.unpriced().count(), recipe.miscs().count());
+}
+
+#[test]
+fn scaled_recipe() {
+    let prices = prices();
+    let mut recipe = load::load(GOEDAR_JSON).unwrap().remove(0);
+    let cost = recipe.cost(&prices);
+    recipe.scale(2.0 * recipe.batch_size);
+    let doubled = recipe.cost(&prices);
+    assert!((doubled.fermentables - 2.0 * cost.fermentables).abs() < 1e-3);
+    assert!((doubled.hops - 2.0 * cost.hops).abs() < 1e-3);
+    // Yeast is bought in whole packs
+    assert_eq!(doubled.yeasts, 2.0 * cost.yeasts);
+    assert!(doubled.per_liter < cost.per_liter);
+}
+
+#[test]
+fn prices_in_the_units_of_the_amount() {
+    let mut prices = PriceList::new();
+    prices.add(Kind::Misc, "Irish Moss".into(), Price::PerKilogram(20.0));
+    // 10 ml of Irish moss
+    let cost = dry_stout(&[]).cost(&prices);
+    let moss = cost
+        .unpriced()
+        .find(|ingredient| ingredient.kind == Kind::Misc);
+    assert_eq!(moss.unwrap().name, "Irish Moss");
+    assert_eq!(cost.miscs, 0.0);
+    let recipe = dry_stout(&[(
+        "<AMOUNT>0.010</AMOUNT>",
+        "<AMOUNT>0.010</AMOUNT><AMOUNT_IS_WEIGHT>TRUE</AMOUNT_IS_WEIGHT>",
+    )]);
+    assert!((recipe.cost(&prices).miscs - 0.2).abs() < 1e-5);
+}
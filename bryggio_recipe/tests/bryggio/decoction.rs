@@ -0,0 +1,84 @@
+use beer_recipe::beerxml_conv::BeerXmlSrc;
+use beer_recipe::bryggio::decoction::{DecoctionError, DecoctionParams};
+use beer_recipe::bryggio::Recipe;
+
+use super::CONVERSION_STEP;
+
+fn step(name: &str, type_: &str, temp: f32, time: f32, infuse_amount: Option<f32>) -> String {
+    format!(
+        "<MASH_STEP>
+            <NAME>{}</NAME>
+            <VERSION>1</VERSION>
+            <TYPE>{}</TYPE>
+            <STEP_TEMP>{}</STEP_TEMP>
+            <STEP_TIME>{}</STEP_TIME>
+            {}
+        </MASH_STEP>",
+        name,
+        type_,
+        temp,
+        time,
+        infuse_amount.map_or(String::new(), |amount| format!(
+            "<INFUSE_AMOUNT>{}</INFUSE_AMOUNT>",
+            amount
+        )),
+    )
+}
+
+/// Dry stout with the mash steps replaced by `steps`
+fn dry_stout(steps: &[String]) -> Recipe<BeerXmlSrc> {
+    super::dry_stout(&[(CONVERSION_STEP, &steps.concat())])
+}
+
+#[test]
+fn double_decoction() {
+    let recipe = dry_stout(&[
+        step("Protein Rest", "Infusion", 52.0, 15.0, Some(12.0)),
+        step("Saccharification", "Decoction", 64.0, 40.0, None),
+        step("Mash Out", "Decoction", 72.0, 10.0, None),
+    ]);
+    let params = DecoctionParams::default();
+    let decoctions = recipe.decoctions(&params).unwrap();
+    assert_eq!(decoctions.len(), 2);
+    let first = &decoctions[0];
+    assert_eq!(first.start_temp, 52.0);
+    assert!((first.mash_temp - 64.0).abs() < 0.2);
+    // Thick mash of 2 l/kg
+    assert!((first.water / first.grain - 2.0).abs() < 1e-3);
+    assert!((first.water + 0.67 * first.grain - first.pull).abs() < 1e-3);
+    assert_eq!(first.schedule.last().unwrap().temp, 100.0);
+    let second = &decoctions[1];
+    assert_eq!(second.start_temp, 64.0);
+    assert!((second.mash_temp - 72.0).abs() < 0.2);
+    // Same mash, smaller step from a hotter start
+    assert!(second.pull < first.pull);
+    // Pulls are rounded to 0.1 l
+    assert!(((first.pull * 10.0).round() - first.pull * 10.0).abs() < 1e-3);
+}
+
+#[test]
+fn impossible_decoctions() {
+    let params = DecoctionParams::default();
+    let recipe = dry_stout(&[step("Dough In", "Decoction", 64.0, 40.0, None)]);
+    assert!(matches!(
+        recipe.decoctions(&params),
+        Err(DecoctionError::NoWater(_))
+    ));
+    let recipe = dry_stout(&[
+        step("Acid Rest", "Infusion", 35.0, 15.0, Some(12.0)),
+        step("Too Hot", "Decoction", 99.0, 10.0, None),
+    ]);
+    assert!(matches!(
+        recipe.decoctions(&params),
+        Err(DecoctionError::Unreachable { .. })
+    ));
+    // Within the mash volume, but more thick mash than the grain makes
+    let recipe = dry_stout(&[
+        step("Acid Rest", "Infusion", 35.0, 15.0, Some(12.0)),
+        step("Hot", "Decoction", 85.0, 10.0, None),
+    ]);
+    assert!(matches!(
+        recipe.decoctions(&params),
+        Err(DecoctionError::Unreachable { .. })
+    ));
+}
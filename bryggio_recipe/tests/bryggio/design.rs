@@ -0,0 +1,90 @@
+use beer_recipe::beerxml_conv::BeerXmlSrc;
+use beer_recipe::bryggio::design::{Design, DesignError, Targets};
+use beer_recipe::bryggio::Recipe;
+
+use super::{beerxml_dry_stout, dry_stout};
+
+#[test]
+fn amounts_hit_targets() {
+    let mut recipe = dry_stout(&[]);
+    let design = Design {
+        grist: vec![70.0, 20.0, 10.0],
+        ibu: vec![100.0],
+    };
+    let targets = Targets {
+        og: 1.045,
+        ibu: 40.0,
+        color: None,
+    };
+    recipe.design(&design, &targets).unwrap();
+    assert!((recipe.estimated_og() - 1.045).abs() < 1e-4);
+    assert!((recipe.ibu() - 40.0).abs() < 0.01);
+    let grist: f32 = recipe.fermentables().map(|f| f.amount).sum();
+    let shares: Vec<f32> = recipe
+        .fermentables()
+        .map(|f| 100.0 * f.amount / grist)
+        .collect();
+    assert!((shares[0] - 70.0).abs() < 0.01 && (shares[2] - 10.0).abs() < 0.01);
+}
+
+#[test]
+fn color_malts_adjust_color() {
+    let mut recipe = dry_stout(&[]);
+    let design = Design::from_recipe(&recipe);
+    assert_eq!(design.ibu, vec![100.0]);
+    let targets = Targets {
+        og: 1.042,
+        ibu: 35.0,
+        color: Some(30.0),
+    };
+    recipe.design(&design, &targets).unwrap();
+    assert!((recipe.estimated_og() - 1.042).abs() < 1e-4);
+    assert!((recipe.estimated_color() - 30.0).abs() < 0.1);
+    // Pale malt and flaked barley keep their ratio
+    let amounts: Vec<f32> = recipe.fermentables().map(|f| f.amount).collect();
+    assert!((amounts[0] / amounts[1] - 2.27 / 0.91).abs() < 1e-3);
+
+    let too_dark = Targets {
+        color: Some(500.0),
+        ..targets
+    };
+    assert!(matches!(
+        recipe.design(&design, &too_dark),
+        Err(DesignError::UnreachableColor(_))
+    ));
+    let missing = Design {
+        grist: vec![100.0],
+        ..design
+    };
+    assert!(matches!(
+        recipe.design(&missing, &targets),
+        Err(DesignError::ShareCount { .. })
+    ));
+}
+
+#[test]
+fn failed_design_leaves_recipe_unchanged() {
+    let mut xml = beerxml_dry_stout(&[]);
+    xml.hops.hop[0].alpha = 0.0;
+    let mut recipe: Recipe<BeerXmlSrc> = xml.into();
+    let amounts: Vec<f32> = recipe.fermentables().map(|f| f.amount).collect();
+    let og = recipe.original_gravity().specific_gravity();
+    let design = Design {
+        grist: vec![70.0, 20.0, 10.0],
+        ibu: vec![100.0],
+    };
+    let targets = Targets {
+        og: 1.045,
+        ibu: 40.0,
+        color: None,
+    };
+    assert!(matches!(
+        recipe.design(&design, &targets),
+        Err(DesignError::NoBitterness(_))
+    ));
+    assert!(recipe
+        .fermentables()
+        .map(|f| f.amount)
+        .eq(amounts.iter().copied()));
+    assert_eq!(recipe.original_gravity().specific_gravity(), og);
+}
@@ -0,0 +1,42 @@
+use beer_recipe::bryggio::diastatic::Problem;
+
+use super::dry_stout;
+
+#[test]
+fn adjuncts_dilute_the_grist() {
+    let conversion = dry_stout(&[]).conversion();
+    let power = conversion.power.as_ref().unwrap();
+    // 2.27 kg of 45 °Lintner pale malt with 1.36 kg of unmalted and roasted barley
+    assert!((power.lintner - 28.1).abs() < 0.1);
+    assert!((power.windisch_kolbach() - 82.4).abs() < 0.5);
+    assert!(power.unknown.is_empty());
+    assert!(!conversion.self_converts());
+    assert!(matches!(
+        conversion.problems[..],
+        [Problem::NoSelfConversion { .. }]
+    ));
+
+    let conversion = dry_stout(&[("<AMOUNT>2.27</AMOUNT>", "<AMOUNT>4.0</AMOUNT>")]).conversion();
+    assert!(conversion.self_converts());
+    assert!(conversion.problems.is_empty());
+}
+
+#[test]
+fn grains_without_diastatic_power_are_left_out() {
+    let conversion = dry_stout(&[("<DIASTATIC_POWER>0.0</DIASTATIC_POWER>", "")]).conversion();
+    let power = conversion.power.unwrap();
+    assert_eq!(power.unknown, vec!["Barley, Flaked".to_string()]);
+    assert!((power.lintner - 45.0 * 2.27 / 2.72).abs() < 0.1);
+}
+
+#[test]
+fn steeped_adjuncts_are_flagged() {
+    let conversion = dry_stout(&[("<TYPE>All Grain</TYPE>", "<TYPE>Extract</TYPE>")]).conversion();
+    assert_eq!(conversion.power, None);
+    assert_eq!(
+        conversion.problems,
+        vec![Problem::SteepedStarch {
+            name: "Barley, Flaked".into()
+        }]
+    );
+}
@@ -0,0 +1,52 @@
+use beer_recipe::bryggio::diff::Change;
+use beer_recipe::bryggio::ingredient::Kind;
+use beer_recipe::load;
+
+use super::dry_stout;
+
+const GOEDAR_XML: &str = "tests/brewfather/Brewfather_BeerXML_Goedar_20200215.xml";
+const GOEDAR_JSON: &str = "tests/brewfather/Brewfather_RECIPE_Goedar_20200215.json";
+
+#[test]
+fn same_recipe_has_empty_diff() {
+    let recipe = load::load(GOEDAR_XML).unwrap().remove(0);
+    let diff = recipe.diff(&recipe);
+    assert!(diff.is_empty());
+    assert!(!diff.ibu.is_changed());
+}
+
+#[test]
+fn dry_hop_times_differ_between_exports() {
+    let xml = load::load(GOEDAR_XML).unwrap().remove(0);
+    let json = load::load(GOEDAR_JSON).unwrap().remove(0);
+    let diff = xml.diff(&json);
+    let dry_hops: Vec<_> = diff
+        .ingredients
+        .iter()
+        .filter(|change| change.kind == Kind::Hop)
+        .collect();
+    assert_eq!(dry_hops.len(), 2);
+    for change in dry_hops {
+        assert_eq!(change.use_.as_deref(), Some("Dry Hop"));
+        match change.change {
+            Change::Changed {
+                time: Some(time), ..
+            } => assert_eq!(time.to, 4.0 * 24.0 * 60.0),
+            _ => panic!("Expected a changed dry hop time, got {:?}", change.change),
+        }
+    }
+    assert!(diff.to_string().contains("~ Hop Citra (Dry Hop)"));
+    let json = serde_json::to_value(&diff).unwrap();
+    assert_eq!(json["from"], "Goe dar");
+}
+
+#[test]
+fn grist_change_despite_specified_gravities() {
+    let recipe = dry_stout(&[]);
+    let stronger = dry_stout(&[("<AMOUNT>2.27</AMOUNT>", "<AMOUNT>3.27</AMOUNT>")]);
+    assert_eq!(recipe.original_gravity(), stronger.original_gravity());
+    let diff = recipe.diff(&stronger);
+    assert_eq!(diff.ingredients.len(), 1);
+    assert!(diff.og.change() > 0.005);
+    assert!(diff.abv.change() > 0.5);
+}
@@ -0,0 +1,26 @@
+use beer_recipe::bryggio::efficiency::Readings;
+use beer_recipe::load;
+
+const GOEDAR_XML: &str = "tests/brewfather/Brewfather_BeerXML_Goedar_20200215.xml";
+
+#[test]
+fn measured_brewhouse_efficiency_reproduces_og() {
+    let mut recipe = load::load(GOEDAR_XML).unwrap().remove(0);
+    let readings = Readings {
+        mash_water: Some(18.0),
+        first_wort_gravity: Some(1.080),
+        pre_boil_gravity: Some(1.052),
+        pre_boil_volume: Some(recipe.pre_boil_volume().liters()),
+        og: Some(1.064),
+        batch_volume: Some(recipe.batch_size),
+    };
+    let breakdown = recipe.efficiency_breakdown(&readings);
+    let (conversion, mash, lauter) = (
+        breakdown.conversion.unwrap(),
+        breakdown.mash.unwrap(),
+        breakdown.lauter.unwrap(),
+    );
+    assert!((mash - conversion * lauter / 100.0).abs() < 1e-3);
+    recipe.set_efficiency(breakdown.brewhouse.unwrap());
+    assert!((recipe.estimated_og() - 1.064).abs() < 2e-3);
+}
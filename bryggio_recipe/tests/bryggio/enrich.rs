@@ -0,0 +1,62 @@
+use beer_recipe::bryggio::enrich;
+use beer_recipe::bryggio::ingredient::Kind;
+use beer_recipe::bryggio::library::Library;
+use beerxml::hop::Form;
+
+use super::beerxml_dry_stout;
+
+fn dry_stout() -> beerxml::Recipe {
+    // As exported by a tool that leaves out optional fields
+    beerxml_dry_stout(&[
+        ("<ATTENUATION>73.0</ATTENUATION>", ""),
+        ("<DIASTATIC_POWER>45.0</DIASTATIC_POWER>", ""),
+        ("<NAME>Irish Ale</NAME>", "<NAME>Irish Ale Yeast</NAME>"),
+    ])
+}
+
+#[test]
+fn fuzzy_matches_are_recorded() {
+    let mut recipe = dry_stout();
+    let enrichment = enrich::enrich(&mut recipe, &Library::bundled());
+
+    let hop = &recipe.hops.hop[0];
+    assert_eq!(hop.form, Some(Form::Pellet));
+    assert!(hop.hsi.is_some());
+    let form = enrichment
+        .inferred
+        .iter()
+        .find(|inferred| inferred.kind == Kind::Hop && inferred.field == "form")
+        .unwrap();
+    assert_eq!(form.name, "Goldings, East Kent");
+    assert_eq!(form.reference, "East Kent Goldings");
+    assert_eq!(form.similarity, 1.0);
+
+    let malt = &recipe.fermentables.fermentable[0];
+    assert_eq!(malt.diastatic_power, Some(140.0));
+    assert!(enrichment.inferred.iter().any(|inferred| {
+        inferred.name == "Pale Malt (2 row) UK"
+            && inferred.reference == "Pale Malt (2 Row)"
+            && inferred.field == "diastatic_power"
+            && inferred.similarity < 1.0
+    }));
+
+    // Matched by product id
+    assert_eq!(recipe.yeasts.yeast[0].attenuation, Some(73.0));
+    assert!(enrichment
+        .inferred
+        .iter()
+        .any(|inferred| inferred.reference == "Irish Ale" && inferred.field == "attenuation"));
+
+    assert!(enrichment
+        .unmatched
+        .contains(&(Kind::Fermentable, "Black Barley".to_string())));
+}
+
+#[test]
+fn complete_recipe_is_unchanged() {
+    let mut recipe = dry_stout();
+    let library = Library::bundled();
+    enrich::enrich(&mut recipe, &library);
+    let enrichment = enrich::enrich(&mut recipe, &library);
+    assert!(enrichment.inferred.is_empty());
+}
@@ -0,0 +1,149 @@
+use beer_recipe::beerxml_conv::BeerXmlSrc;
+use beer_recipe::bryggio::cost::{Price, PriceList};
+use beer_recipe::bryggio::hop_schedule::{self, Objective, ScheduleError, Target};
+use beer_recipe::bryggio::ingredient::Kind;
+use beer_recipe::bryggio::inventory::{Inventory, Lot};
+use beer_recipe::bryggio::library::Library;
+use beer_recipe::bryggio::Recipe;
+
+/// Dry stout with a single 10 min aroma addition
+fn dry_stout() -> Recipe<BeerXmlSrc> {
+    super::dry_stout(&[
+        ("<AMOUNT>0.0638</AMOUNT>", "<AMOUNT>0.02</AMOUNT>"),
+        ("<TIME>60.0</TIME>", "<TIME>10.0</TIME>"),
+    ])
+}
+
+fn stock() -> (Vec<beerxml::Hop>, Inventory) {
+    let library = Library::bundled();
+    let candidates = vec![
+        library.hop("Magnum").unwrap().clone(),
+        library.hop("Cascade").unwrap().clone(),
+    ];
+    let mut inventory = Inventory::new();
+    let mut magnum = Lot::new(Kind::Hop, "Magnum".into(), 0.01);
+    magnum.alpha = Some(14.0);
+    inventory.add(magnum);
+    inventory.add(Lot::new(Kind::Hop, "Cascade".into(), 0.1));
+    (candidates, inventory)
+}
+
+#[test]
+fn least_hops_for_ibu() {
+    let mut recipe = dry_stout();
+    let (candidates, inventory) = stock();
+    let fixed = recipe.ibu();
+    let schedule = hop_schedule::optimize(
+        &recipe,
+        &candidates,
+        &inventory,
+        &[60.0, 30.0],
+        Target::Ibu(40.0),
+        Objective::Usage,
+    )
+    .unwrap();
+    assert!((schedule.ibu - 40.0).abs() < 0.4);
+    assert!(schedule.additions.iter().all(|hop| hop.time == 60.0));
+    // All the high alpha Magnum is used before Cascade
+    let magnum = &schedule.additions[0];
+    assert_eq!(magnum.name, "Magnum");
+    assert_eq!(magnum.alpha, 14.0);
+    assert!((magnum.amount - 0.01).abs() < 1e-6);
+    assert_eq!(schedule.additions[1].name, "Cascade");
+
+    schedule.apply(&mut recipe);
+    assert!(recipe.ibu() > fixed);
+    assert!((recipe.ibu() - 40.0).abs() < 0.4);
+}
+
+#[test]
+fn cheapest_hops_for_bu_gu() {
+    let recipe = dry_stout();
+    let (candidates, inventory) = stock();
+    let mut prices = PriceList::new();
+    prices.add(Kind::Hop, "Magnum".into(), Price::PerKilogram(100.0));
+    prices.add(Kind::Hop, "Cascade".into(), Price::PerKilogram(20.0));
+    let schedule = hop_schedule::optimize(
+        &recipe,
+        &candidates,
+        &inventory,
+        &[60.0],
+        Target::BuGu(0.8),
+        Objective::Cost(&prices),
+    )
+    .unwrap();
+    let og = recipe.original_gravity().points();
+    assert!((schedule.ibu - 0.8 * og).abs() < 0.01 * 0.8 * og);
+    assert_eq!(schedule.additions.len(), 1);
+    let cascade = &schedule.additions[0];
+    assert_eq!(cascade.name, "Cascade");
+    assert!((schedule.cost.unwrap() - 20.0 * cascade.amount).abs() < 1e-4);
+
+    // Ranked by the price per kg, paid in whole packs
+    let mut prices = PriceList::new();
+    prices.add(Kind::Hop, "Magnum".into(), Price::PerKilogram(100.0));
+    prices.add(
+        Kind::Hop,
+        "Cascade".into(),
+        Price::PerPack {
+            size: 0.3,
+            price: 6.0,
+        },
+    );
+    let schedule = hop_schedule::optimize(
+        &recipe,
+        &candidates,
+        &inventory,
+        &[60.0],
+        Target::BuGu(0.8),
+        Objective::Cost(&prices),
+    )
+    .unwrap();
+    assert_eq!(schedule.additions[0].name, "Cascade");
+    assert_eq!(schedule.cost, Some(6.0));
+
+    // A pack without a size has no price per kg
+    let mut prices = PriceList::new();
+    prices.add(Kind::Hop, "Magnum".into(), Price::PerKilogram(100.0));
+    prices.add(
+        Kind::Hop,
+        "Cascade".into(),
+        Price::PerPack {
+            size: 0.0,
+            price: 0.0,
+        },
+    );
+    let result = hop_schedule::optimize(
+        &recipe,
+        &candidates,
+        &inventory,
+        &[60.0],
+        Target::BuGu(0.8),
+        Objective::Cost(&prices),
+    );
+    assert!(matches!(result, Err(ScheduleError::NotEnoughHops(_))));
+}
+
+#[test]
+fn targets_out_of_reach() {
+    let recipe = dry_stout();
+    let (candidates, inventory) = stock();
+    let optimize = |target| {
+        hop_schedule::optimize(
+            &recipe,
+            &candidates,
+            &inventory,
+            &[60.0],
+            target,
+            Objective::Usage,
+        )
+    };
+    assert!(matches!(
+        optimize(Target::Ibu(1.0)),
+        Err(ScheduleError::TargetExceeded(_))
+    ));
+    assert!(matches!(
+        optimize(Target::Ibu(200.0)),
+        Err(ScheduleError::NotEnoughHops(_))
+    ));
+}
@@ -0,0 +1,69 @@
+use beer_recipe::bryggio::bitterness::{HopStand, HopStorage, Model, Packaging};
+
+use super::dry_stout;
+
+#[test]
+fn utilization_per_hop() {
+    let recipe = dry_stout(&[]);
+    let tinseth = recipe.ibu_report(&Model::Boil(recipe.ibu_method()));
+    let goldings = &tinseth.hops[0];
+    // 60 min boil from 20.82 to 18.93 l and from 1.0327 to 1.036
+    assert!((goldings.volume - 19.875).abs() < 1e-3);
+    assert!((goldings.gravity - 1.0344).abs() < 1e-4);
+    assert!((goldings.utilization - 26.55).abs() < 0.01);
+    assert!((goldings.ibu - 42.61).abs() < 0.01);
+    assert!((tinseth.bu_gu - 42.61 / 36.0).abs() < 1e-3);
+
+    let comparison = recipe.ibu_comparison(HopStand::default());
+    assert_eq!(comparison.len(), 5);
+    // mIBU without a hop stand is Tinseth
+    assert!((comparison[3].ibu - goldings.ibu).abs() < 1e-3);
+    // SMPH: 47.6 % isomerized after 60 min, 60 % of it surviving, and 2.2 % non-IAA,
+    // of 160.5 mg/l alpha acids
+    let smph = &comparison[4].hops[0];
+    assert!((smph.utilization - 21.97).abs() < 0.01);
+    assert!((smph.ibu - 35.26).abs() < 0.01);
+}
+
+#[test]
+fn aged_hops_give_less_bitterness() {
+    let mut recipe = dry_stout(&[]);
+    let fresh = recipe.ibu();
+    let storage = HopStorage {
+        temp: 20.0,
+        packaging: Packaging::Open,
+        age: 365.0,
+    };
+    recipe.set_hop_storage(Some(storage));
+    // Hops without a Hop Stability Index are not aged
+    assert_eq!(recipe.ibu(), fresh);
+
+    let mut recipe = dry_stout(&[("<HOP>", "<HOP><HSI>30</HSI>")]);
+    assert_eq!(recipe.ibu(), fresh);
+    recipe.set_hop_storage(Some(storage));
+    // Two times 30 % loss
+    assert!((recipe.ibu() - 0.49 * fresh).abs() < 0.01 * fresh);
+}
+
+#[test]
+fn dry_hops_are_in_the_batch_volume() {
+    let recipe = dry_stout(&[(
+        "</HOPS>",
+        "<HOP>
+            <NAME>Cascade</NAME>
+            <VERSION>1</VERSION>
+            <ALPHA>5.5</ALPHA>
+            <AMOUNT>0.03</AMOUNT>
+            <USE>Dry Hop</USE>
+            <TIME>4320.0</TIME>
+        </HOP></HOPS>",
+    )]);
+    let report = recipe.ibu_report(&Model::Smph(HopStand::default()));
+    // A three day dry hop sees the fermenter volume and the OG, not the boil
+    let dry_hop = &report.hops[1];
+    assert_eq!(dry_hop.volume, 18.93);
+    assert_eq!(dry_hop.gravity, 1.036);
+    assert!(dry_hop.ibu > 0.0);
+    let boil = &report.hops[0];
+    assert!(boil.volume > 18.93 && boil.gravity < 1.036);
+}
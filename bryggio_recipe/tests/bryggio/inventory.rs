@@ -0,0 +1,51 @@
+use beer_recipe::bryggio::ingredient::Kind;
+use beer_recipe::bryggio::inventory::{Inventory, InventoryError, Lot};
+use beer_recipe::load;
+
+const GOEDAR_JSON: &str = "tests/brewfather/Brewfather_RECIPE_Goedar_20200215.json";
+
+#[test]
+fn deduct_brewed_batch() {
+    let recipe = load::load(GOEDAR_JSON).unwrap().remove(0);
+    let mut inventory = Inventory::new();
+    for fermentable in recipe.fermentables() {
+        inventory.add(Lot::new(Kind::Fermentable, fermentable.name.clone(), 25.0));
+    }
+    for hop in recipe.hops() {
+        let mut lot = Lot::new(Kind::Hop, hop.name.to_uppercase(), 0.1);
+        lot.harvested = Some("2019-09-01".into());
+        inventory.add(lot);
+    }
+    for yeast in recipe.yeasts() {
+        inventory.add(Lot::new(Kind::Yeast, yeast.name.clone(), yeast.amount));
+    }
+    for misc in recipe.miscs() {
+        inventory.add(Lot::new(Kind::Misc, misc.name.clone(), 1.0));
+    }
+    assert!(inventory.is_brewable(&recipe));
+
+    let citra: f32 = recipe
+        .hops()
+        .filter(|hop| hop.name == "Citra")
+        .map(|hop| hop.amount)
+        .sum();
+    let before = inventory.available(Kind::Hop, "Citra");
+    inventory.deduct(&recipe).unwrap();
+    assert!((inventory.available(Kind::Hop, "Citra") - (before - citra)).abs() < 1e-5);
+    assert_eq!(
+        inventory.available(Kind::Yeast, &recipe.yeasts().next().unwrap().name),
+        0.0
+    );
+
+    let shortfalls = inventory.shortfalls(&recipe);
+    assert!(!inventory.is_brewable(&recipe));
+    assert!(shortfalls
+        .iter()
+        .any(|shortfall| shortfall.kind == Kind::Yeast));
+    let remaining = inventory.clone();
+    match inventory.deduct(&recipe) {
+        Err(InventoryError::Shortfalls(errors)) => assert_eq!(errors, shortfalls),
+        Ok(()) => panic!("Deducted a recipe that is not brewable"),
+    }
+    assert_eq!(inventory, remaining);
+}
@@ -0,0 +1,51 @@
+use beer_recipe::beerxml_conv::BeerXmlSrc;
+use beer_recipe::bryggio::ingredient::Kind;
+use beer_recipe::bryggio::library::Library;
+use beer_recipe::bryggio::Recipe;
+use beerxml::yeast::Flocculation;
+
+use super::beerxml_dry_stout;
+
+#[test]
+fn search_by_name_and_laboratory() {
+    let library = Library::bundled();
+    let found = library.search("crystal 60");
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].kind(), Kind::Fermentable);
+    assert_eq!(found[0].name(), "Caramel/Crystal Malt 60L");
+
+    let wyeast: Vec<&str> = library
+        .search("wyeast ale")
+        .iter()
+        .map(|ingredient| ingredient.name())
+        .collect();
+    assert!(wyeast.contains(&"Irish Ale"));
+    assert!(wyeast.iter().all(|name| library.yeast(name).is_some()));
+    assert!(library.search("no such ingredient").is_empty());
+}
+
+#[test]
+fn complete_imported_recipe() {
+    // As exported by a tool that leaves out optional fields
+    let mut recipe = beerxml_dry_stout(&[
+        ("<FLOCCULATION>Medium</FLOCCULATION>", ""),
+        ("<MIN_TEMPERATURE>16.7</MIN_TEMPERATURE>", ""),
+        ("<DIASTATIC_POWER>0.0</DIASTATIC_POWER>", ""),
+        ("<DIASTATIC_POWER>0.0</DIASTATIC_POWER>", ""),
+    ]);
+    let filled = Library::bundled().complete(&mut recipe);
+    // Flaked barley, the yeast and Irish moss are in the library, the others under other names
+    assert_eq!(filled, 4);
+    assert!(recipe.miscs.misc[0].use_for.is_some());
+    let yeast = &recipe.yeasts.yeast[0];
+    assert_eq!(yeast.flocculation, Some(Flocculation::Medium));
+    assert_eq!(yeast.min_temperature, Some(16.0));
+    assert_eq!(yeast.max_temperature, Some(22.2));
+    let barley = &recipe.fermentables.fermentable[1];
+    assert_eq!(barley.name, "Barley, Flaked");
+    assert_eq!(barley.diastatic_power, Some(0.0));
+    assert_eq!(recipe.fermentables.fermentable[2].diastatic_power, None);
+
+    let recipe: Recipe<BeerXmlSrc> = recipe.into();
+    assert!(recipe.ibu() > 0.0);
+}
@@ -0,0 +1,58 @@
+use beer_recipe::load::{self, Format, RecipeSource};
+use beerxml::hop::Use;
+
+const GOEDAR_XML: &str = "tests/brewfather/Brewfather_BeerXML_Goedar_20200215.xml";
+const GOEDAR_JSON: &str = "tests/brewfather/Brewfather_RECIPE_Goedar_20200215.json";
+
+#[test]
+fn same_recipe_in_beerxml_and_brewfather_json() {
+    let xml = load::load(GOEDAR_XML).unwrap();
+    let json = load::load(GOEDAR_JSON).unwrap();
+    assert_eq!(xml.len(), 1);
+    assert_eq!(json.len(), 1);
+    let (xml, json) = (&xml[0], &json[0]);
+    assert!(matches!(xml.recipe_src(), RecipeSource::BeerXml(_)));
+    assert!(matches!(json.recipe_src(), RecipeSource::Brewfather(_)));
+    assert_eq!(xml.name, json.name);
+    assert_eq!(xml.hops().count(), json.hops().count());
+    for (xml_hop, json_hop) in xml.hops().zip(json.hops()) {
+        assert_eq!(xml_hop.use_, json_hop.use_);
+        assert!((xml_hop.amount - json_hop.amount).abs() < 1e-4);
+        // Brewfather exports dry hop times in days, not minutes, to BeerXML
+        if xml_hop.use_ != Use::DryHop {
+            assert!((xml_hop.time - json_hop.time).abs() < 1e-2);
+        }
+    }
+    assert!((xml.ibu() - json.ibu()).abs() < 0.5);
+}
+
+#[test]
+fn recipe_list() {
+    let xml = format!(
+        "<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?>\n<RECIPES>\n{}\n{}\n</RECIPES>",
+        strip_declaration(GOEDAR_XML),
+        strip_declaration(GOEDAR_XML)
+    );
+    assert_eq!(load::detect(&xml), Some(Format::BeerXmlList));
+    assert_eq!(load::from_str(&xml).unwrap().len(), 2);
+}
+
+#[test]
+fn unknown_format() {
+    assert!(load::from_str("name,og\nGoedar,1.062").is_err());
+}
+
+fn strip_declaration(path: &str) -> String {
+    let contents = std::fs::read_to_string(path).unwrap();
+    contents[contents.find("?>").unwrap() + 2..].to_string()
+}
+
+#[test]
+fn iso_8859_1_recipe() {
+    let xml = std::fs::read_to_string(GOEDAR_XML)
+        .unwrap()
+        .replace("Goe&#32;dar", "Gö dar");
+    let latin1: Vec<u8> = xml.chars().map(|c| c as u8).collect();
+    let recipes = load::from_bytes(&latin1).unwrap();
+    assert_eq!(recipes[0].name, "Gö dar");
+}
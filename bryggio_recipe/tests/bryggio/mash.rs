@@ -0,0 +1,53 @@
+use beer_recipe::beerxml_conv::BeerXmlSrc;
+use beer_recipe::bryggio::mash::Heater;
+use beer_recipe::bryggio::Recipe;
+use beer_recipe::load;
+
+const GOEDAR_JSON: &str = "tests/brewfather/Brewfather_RECIPE_Goedar_20200215.json";
+
+/// Dry stout with a mash out step ramped in 4 min
+fn dry_stout() -> Recipe<BeerXmlSrc> {
+    super::dry_stout(&[(
+        "</MASH_STEP>",
+        "</MASH_STEP>
+        <MASH_STEP>
+            <NAME>Mash Out</NAME>
+            <VERSION>1</VERSION>
+            <TYPE>Temperature</TYPE>
+            <STEP_TEMP>76.0</STEP_TEMP>
+            <STEP_TIME>10.0</STEP_TIME>
+            <RAMP_TIME>4.0</RAMP_TIME>
+        </MASH_STEP>",
+    )])
+}
+
+#[test]
+fn infusion_and_mash_out() {
+    let recipe = dry_stout();
+    let profile = recipe.mash_profile();
+    assert_eq!(profile.duration(), 74.0);
+    assert_eq!(profile.temp_at(0.0), Some(68.0));
+    assert_eq!(profile.temp_at(62.0), Some(72.0));
+
+    let heater = Heater::new(2.0);
+    let heating = recipe.mash_heating(&heater);
+    // 10 l of strike water for 3.63 kg of grain at 22 °C
+    let strike = &heating[0];
+    assert!((strike.water_temp.unwrap() - 74.78).abs() < 0.01);
+    assert!((strike.energy - 0.637).abs() < 0.001);
+    assert!((strike.heating_time - 19.1).abs() < 0.1);
+    // 48 kJ/K mash heated by 8 °C in 4 min
+    let mash_out = &heating[1];
+    assert!((mash_out.power.unwrap() - 1.6).abs() < 0.01);
+    assert!(mash_out.keeps_ramp(&heater));
+    assert!(!mash_out.keeps_ramp(&Heater::new(1.5)));
+}
+
+#[test]
+fn brewfather_mash_steps() {
+    let recipe = load::load(GOEDAR_JSON).unwrap().remove(0);
+    let profile = recipe.mash_profile();
+    // Ramp from 65 to 75.6 °C at 1 °C/min
+    assert!((profile.duration() - 80.6).abs() < 1e-3);
+    assert_eq!(profile.temp_at(30.0), Some(65.0));
+}
@@ -0,0 +1,50 @@
+use std::fs;
+
+use beer_recipe::beerxml_conv::BeerXmlSrc;
+use beer_recipe::bryggio::Recipe;
+
+mod analytics;
+mod batch;
+mod cost;
+mod decoction;
+mod design;
+mod diastatic;
+mod diff;
+mod efficiency;
+mod enrich;
+mod hop_schedule;
+mod ibu;
+mod inventory;
+mod library;
+mod load;
+mod mash;
+mod pitch;
+mod quantity;
+mod sparge;
+mod substitution;
+mod system;
+
+/// The only mash step of the dry stout
+pub const CONVERSION_STEP: &str = "<MASH_STEP>
+            <NAME>Conversion Step, 68C </NAME>
+            <VERSION>1</VERSION>
+            <TYPE>Infusion</TYPE>
+            <STEP_TEMP>68.0</STEP_TEMP>
+            <STEP_TIME>60.0</STEP_TIME>
+<INFUSE_AMOUNT>10.0</INFUSE_AMOUNT>
+      </MASH_STEP>";
+
+/// The BeerXML dry stout with the first occurrence of each `(from, to)` replaced in order
+pub fn beerxml_dry_stout(replacements: &[(&str, &str)]) -> beerxml::Recipe {
+    let mut contents = fs::read_to_string("tests/beerxml/data/recipe.xml").unwrap();
+    for (from, to) in replacements {
+        assert!(contents.contains(from), "{}", from);
+        contents = contents.replacen(from, to, 1);
+    }
+    serde_xml_rs::from_str(&contents).unwrap()
+}
+
+/// The dry stout with the first occurrence of each `(from, to)` replaced in order
+pub fn dry_stout(replacements: &[(&str, &str)]) -> Recipe<BeerXmlSrc> {
+    beerxml_dry_stout(replacements).into()
+}
@@ -0,0 +1,44 @@
+use beer_recipe::bryggio::pitch::StarterParams;
+
+use super::dry_stout;
+
+#[test]
+fn liquid_yeast_with_a_starter() {
+    let recipe = dry_stout(&[]);
+    let plans = recipe.pitch_plans(0.0, Some(&StarterParams::default()));
+    assert_eq!(plans.len(), 1);
+    let plan = &plans[0];
+    assert_eq!(plan.product_id.as_deref(), Some("1084"));
+    // 0.75 million cells / (ml °P) in 18.93 l of 9.0 °P wort
+    assert!((plan.required_cells - 127.7).abs() < 0.5);
+    assert_eq!(plan.cells_per_pack, 97.0);
+    assert_eq!(plan.packs, 2);
+    let starter = plan.starter.as_ref().unwrap();
+    assert_eq!(starter.packs, 1);
+    assert!(starter.final_cells() >= plan.required_cells);
+}
+
+#[test]
+fn dry_yeast_by_weight() {
+    let recipe = dry_stout(&[
+        ("<FORM>Liquid</FORM>", "<FORM>Dry</FORM>"),
+        (
+            "<AMOUNT>0.250</AMOUNT>",
+            "<AMOUNT>0.023</AMOUNT><AMOUNT_IS_WEIGHT>TRUE</AMOUNT_IS_WEIGHT>",
+        ),
+    ]);
+    let plan = &recipe.pitch_plans(0.0, Some(&StarterParams::default()))[0];
+    // 23 g of dry yeast, twice a sachet
+    assert!((plan.cells_per_pack - 230.0).abs() < 1e-3);
+    assert_eq!(plan.packs, 1);
+    assert_eq!(plan.starter, None);
+}
+
+#[test]
+fn dead_yeast_has_no_plan() {
+    let recipe = dry_stout(&[]);
+    let plan = &recipe.pitch_plans(365.0, Some(&StarterParams::default()))[0];
+    assert_eq!(plan.viability, 0.0);
+    assert_eq!(plan.packs, 0);
+    assert_eq!(plan.starter, None);
+}
@@ -0,0 +1,46 @@
+use beer_recipe::beerxml_conv::BeerXmlSrc;
+use beer_recipe::bryggio::quantity::UnitSystem;
+use beer_recipe::bryggio::Recipe;
+
+fn dry_stout(equipment: &str) -> Recipe<BeerXmlSrc> {
+    super::dry_stout(&[("<HOPS>", &format!("{}<HOPS>", equipment))])
+}
+
+#[test]
+fn equipment_quantities() {
+    assert_eq!(dry_stout("").tun_weight(), None);
+    let recipe = dry_stout(
+        "<EQUIPMENT>
+            <NAME>Cooler</NAME>
+            <VERSION>1</VERSION>
+            <BOIL_SIZE>26.5</BOIL_SIZE>
+            <BATCH_SIZE>18.93</BATCH_SIZE>
+            <TUN_VOLUME>37.85</TUN_VOLUME>
+            <TUN_WEIGHT>2.27</TUN_WEIGHT>
+        </EQUIPMENT>",
+    );
+    assert!((recipe.equipment_boil_volume().unwrap().gallons() - 7.0).abs() < 0.01);
+    assert!((recipe.tun_volume().unwrap().gallons() - 10.0).abs() < 0.01);
+    assert!((recipe.tun_weight().unwrap().pounds() - 5.0).abs() < 0.01);
+}
+
+#[test]
+fn recipe_in_unit_system() {
+    let recipe = dry_stout("");
+    assert_eq!(
+        recipe.display(UnitSystem::Metric).to_string(),
+        "Dry Stout
+Batch: 18.93 l, boil: 20.82 l, 60 min
+OG: 9.0 °P, FG: 3.1 °P, IBU: 42.6, color: 71 EBC
+  2.27 kg Pale Malt (2 row) UK
+  910 g Barley, Flaked
+  450 g Black Barley
+  64 g Goldings, East Kent, 60 min
+  Irish Ale
+"
+    );
+    let imperial = recipe.display(UnitSystem::Imperial).to_string();
+    assert!(imperial.starts_with("Dry Stout\nBatch: 5.00 gal, boil: 5.50 gal, 60 min\n"));
+    assert!(imperial.contains("OG: 1.036, FG: 1.012, IBU: 42.6, color: 35.8 SRM"));
+    assert!(imperial.contains("  2.25 oz Goldings, East Kent, 60 min"));
+}
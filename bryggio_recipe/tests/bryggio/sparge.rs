@@ -0,0 +1,88 @@
+use beer_recipe::beerxml_conv::BeerXmlSrc;
+use beer_recipe::bryggio::sparge::{self, Method, SpargeError, SpargeParams};
+use beer_recipe::bryggio::Recipe;
+
+use super::beerxml_dry_stout;
+
+fn dry_stout(equipment: &str) -> Recipe<BeerXmlSrc> {
+    super::dry_stout(&[("<HOPS>", &format!("{}<HOPS>", equipment))])
+}
+
+#[test]
+fn lauter_efficiency_by_method() {
+    let recipe = dry_stout("");
+    let params = SpargeParams::default();
+    let plans = sparge::compare(&recipe, &params).unwrap();
+    let (fly, batch, no_sparge) = (&plans[0], &plans[1], &plans[2]);
+    assert!(fly.lauter_efficiency > batch.lauter_efficiency);
+    assert!(batch.lauter_efficiency > no_sparge.lauter_efficiency);
+
+    // 10 l of mash water, of which 3.63 l stay in the grain
+    assert_eq!(batch.mash_water, 10.0);
+    assert!((batch.sparge_water - (20.82 - 6.37)).abs() < 1e-3);
+    assert!((batch.lauter_efficiency - 92.7).abs() < 0.1);
+    assert_eq!(batch.runnings.len(), 2);
+    assert!(batch.runnings[0].gravity > batch.runnings[1].gravity);
+    assert!((batch.pre_boil_volume - 20.82).abs() < 1e-3);
+    assert!(batch.pre_boil_gravity < batch.runnings[0].gravity);
+
+    // The full pre-boil volume and the retained wort are mashed
+    assert!((no_sparge.mash_water - 24.45).abs() < 1e-3);
+    assert_eq!(no_sparge.sparge_water, 0.0);
+    assert!((no_sparge.lauter_efficiency - 85.2).abs() < 0.1);
+    assert!(no_sparge.pre_boil_gravity < batch.pre_boil_gravity);
+}
+
+#[test]
+fn batches_fit_the_tun() {
+    let recipe = dry_stout(
+        "<EQUIPMENT>
+            <NAME>Small Cooler</NAME>
+            <VERSION>1</VERSION>
+            <BOIL_SIZE>20.82</BOIL_SIZE>
+            <BATCH_SIZE>18.93</BATCH_SIZE>
+            <TUN_VOLUME>12.0</TUN_VOLUME>
+            <LAUTER_DEADSPACE>0.5</LAUTER_DEADSPACE>
+        </EQUIPMENT>",
+    );
+    let plan = recipe
+        .sparge_plan(Method::Batch, &SpargeParams::default())
+        .unwrap();
+    assert!((plan.retained - 4.13).abs() < 1e-3);
+    // 15 l of sparge water in batches of at most 5.44 l
+    assert_eq!(plan.runnings.len(), 4);
+    let sparges = &plan.runnings[1..];
+    assert!(sparges
+        .iter()
+        .all(|runnings| (runnings.sparge_water - plan.sparge_water / 3.0).abs() < 1e-4));
+    assert!(sparges
+        .windows(2)
+        .all(|pair| pair[0].gravity > pair[1].gravity));
+}
+
+#[test]
+fn mash_water_absorbed_by_the_grain() {
+    let mut recipe = beerxml_dry_stout(&[]);
+    recipe.mash.mash_steps.mash_step[0].infuse_amount = Some(2.0);
+    let recipe: Recipe<BeerXmlSrc> = recipe.into();
+    let plan = recipe
+        .sparge_plan(Method::Batch, &SpargeParams::default())
+        .unwrap();
+    // No first runnings, the sparge rinses out the extract left in the grain
+    assert_eq!(plan.runnings[0].volume, 0.0);
+    assert!(plan.lauter_efficiency > 0.0 && plan.lauter_efficiency < 100.0);
+    assert!(plan.pre_boil_gravity > 1.0);
+}
+
+#[test]
+fn nothing_to_lauter() {
+    let mut recipe = beerxml_dry_stout(&[]);
+    for fermentable in &mut recipe.fermentables.fermentable {
+        fermentable.type_ = beerxml::fermentable::Type::DryExtract;
+    }
+    let recipe: Recipe<BeerXmlSrc> = recipe.into();
+    assert_eq!(
+        recipe.sparge_plan(Method::Fly, &SpargeParams::default()),
+        Err(SpargeError::NoGrain)
+    );
+}
@@ -0,0 +1,109 @@
+use std::fs;
+
+use beer_recipe::beerxml_conv::BeerXmlSrc;
+use beer_recipe::bryggio::library::Library;
+use beer_recipe::bryggio::{substitution, Recipe};
+use beerxml::fermentable::Type;
+use beerxml::yeast::{self, Flocculation, Form};
+use beerxml::{hop, Fermentable, Yeast};
+
+fn dry_stout() -> Recipe<BeerXmlSrc> {
+    super::dry_stout(&[])
+}
+
+fn library() -> Library {
+    let contents = fs::read_to_string("tests/beerxml/data/hops.xml").unwrap();
+    let hops: hop::Hops = serde_xml_rs::from_str(&contents).unwrap();
+    let mut library = Library::new();
+    library.hops = hops.hop;
+    library.fermentables = vec![
+        Fermentable::new("Maris Otter".into(), Type::Grain, 0.0, 81.0, 3.0),
+        Fermentable::new("Roasted Barley".into(), Type::Grain, 0.0, 55.0, 300.0),
+        Fermentable::new("Chocolate Malt".into(), Type::Grain, 0.0, 60.0, 350.0),
+        Fermentable::new("Light DME".into(), Type::DryExtract, 0.0, 95.0, 8.0),
+    ];
+    library.yeasts = vec![
+        ale("Dry English Ale", 80.0, Flocculation::High, 18.0, 21.0),
+        ale("Irish Red Ale", 72.0, Flocculation::Medium, 16.0, 22.0),
+        ale("London Ale III", 73.0, Flocculation::High, 18.0, 22.0),
+        ale("Belgian Saison", 85.0, Flocculation::Low, 21.0, 35.0),
+    ];
+    library
+}
+
+fn ale(name: &str, attenuation: f32, flocculation: Flocculation, min: f32, max: f32) -> Yeast {
+    let mut yeast = Yeast::new(name.into(), yeast::Type::Ale, Form::Liquid, 0.125);
+    yeast.attenuation = Some(attenuation);
+    yeast.flocculation = Some(flocculation);
+    yeast.min_temperature = Some(min);
+    yeast.max_temperature = Some(max);
+    yeast
+}
+
+#[test]
+fn hop_substitution_keeps_ibu() {
+    let library = library();
+    let recipe = dry_stout();
+    let substitutions = substitution::hops(&recipe, "Goldings, East Kent", &library);
+    assert_eq!(substitutions.len(), library.hops.len());
+    assert_eq!(substitutions[0].name, "Goldings, B.C.");
+    for substitution in substitutions {
+        let mut substituted = dry_stout();
+        substituted.substitute_hop(substitution);
+        assert!((substituted.ibu() - recipe.ibu()).abs() < 0.01 * recipe.ibu());
+    }
+}
+
+#[test]
+fn fermentable_substitution_keeps_gravity_and_color() {
+    let library = library();
+    let recipe = dry_stout();
+
+    let base = substitution::fermentables(&recipe, "Pale Malt (2 row) UK", &library);
+    assert_eq!(base[0].name, "Maris Otter");
+    let mut substituted = dry_stout();
+    substituted.substitute_fermentable(base[0].clone());
+    let (og, substituted_og) = (
+        recipe.original_gravity().specific_gravity(),
+        substituted.original_gravity().specific_gravity(),
+    );
+    assert!((og - substituted_og).abs() < 1e-4);
+
+    let roast = substitution::fermentables(&recipe, "Black Barley", &library);
+    assert_eq!(roast.len(), 3);
+    assert_eq!(roast[0].name, "Chocolate Malt");
+    let mut substituted = dry_stout();
+    substituted.substitute_fermentable(roast[0].clone());
+    // The extra base malt adds a little color
+    assert!((recipe.estimated_color() - substituted.estimated_color()).abs() < 0.1);
+    assert!((recipe.estimated_og() - substituted.estimated_og()).abs() < 1e-4);
+    assert!(substituted
+        .fermentables()
+        .any(|fermentable| fermentable.name == "Chocolate Malt"));
+}
+
+#[test]
+fn yeast_substitution_ranking() {
+    let recipe = dry_stout();
+    let substitutions = substitution::yeasts(&recipe, "Irish Ale", &library());
+    let names: Vec<&str> = substitutions
+        .iter()
+        .map(|substitution| substitution.name.as_str())
+        .collect();
+    assert_eq!(
+        names,
+        [
+            "Irish Red Ale",
+            "London Ale III",
+            "Dry English Ale",
+            "Belgian Saison"
+        ]
+    );
+    let amount = recipe.yeasts().next().unwrap().amount;
+    assert_eq!(substitutions[0].additions[0].amount, amount);
+}
+
+#[test]
+fn unknown_ingredient() {
+    assert!(substitution::yeasts(&dry_stout(), "Kveik", &library()).is_empty());
+}
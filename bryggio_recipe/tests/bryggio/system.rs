@@ -0,0 +1,99 @@
+use std::fs;
+
+use beer_recipe::beerxml_conv::BeerXmlSrc;
+use beer_recipe::brewfather_conv::BrewfatherRecipe;
+use beer_recipe::bryggio::cost::PriceList;
+use beer_recipe::bryggio::efficiency::Readings;
+use beer_recipe::bryggio::sparge::{Method, SpargeParams};
+use beer_recipe::bryggio::system::{Kind, System};
+use beer_recipe::bryggio::Recipe;
+use beer_recipe::load;
+
+fn dry_stout() -> Recipe<BeerXmlSrc> {
+    super::dry_stout(&[])
+}
+
+#[test]
+fn brew_in_a_bag() {
+    let mut recipe = dry_stout();
+    assert_eq!(recipe.system(), System::traditional());
+    let params = SpargeParams::default();
+    let tun = recipe.sparge_plan(Method::NoSparge, &params).unwrap();
+    let batch = recipe.lauter_plan(&params).unwrap();
+    let (efficiency, og) = (recipe.efficiency(), recipe.estimated_og());
+
+    recipe.set_system(System::brew_in_a_bag());
+    let bag = recipe.lauter_plan(&params).unwrap();
+    assert_eq!(bag.method, Method::NoSparge);
+    // 20.82 l pre-boil and 0.5 l/kg left in 3.63 kg of squeezed grain
+    assert!((bag.mash_water - 22.635).abs() < 1e-3);
+    assert_eq!(bag.sparge_water, 0.0);
+    assert!((bag.pre_boil_volume - 20.82).abs() < 1e-3);
+    assert!((bag.lauter_efficiency - 92.0).abs() < 0.1);
+    assert!(bag.lauter_efficiency > tun.lauter_efficiency);
+
+    // The squeezed bag keeps more wort than a batch sparge rinses out
+    let expected = efficiency * bag.lauter_efficiency / batch.lauter_efficiency;
+    assert!((recipe.efficiency() - expected).abs() < 1e-3);
+    assert!(recipe.estimated_og() < og);
+    // The full volume is the mash water of the conversion
+    let readings = Readings {
+        first_wort_gravity: Some(1.030),
+        ..Readings::default()
+    };
+    assert!(recipe.efficiency_breakdown(&readings).conversion.is_some());
+
+    // Less water is bought for the squeezed grain
+    let mut prices = PriceList::new();
+    prices.water = Some(1.0);
+    assert!((recipe.cost(&prices).water - 22.635).abs() < 1e-3);
+    recipe.set_system(System::traditional());
+    assert!((recipe.cost(&prices).water - 24.45).abs() < 1e-3);
+    assert!((recipe.efficiency() - efficiency).abs() < 1e-3);
+}
+
+#[test]
+fn all_in_one() {
+    let mut recipe = dry_stout();
+    recipe.set_system(System::grainfather());
+    let system = recipe.system();
+    assert_eq!(system.kind, Kind::AllInOne);
+    let plan = recipe.lauter_plan(&SpargeParams::default()).unwrap();
+    assert_eq!(plan.method, Method::Fly);
+    // The water below the malt pipe drains with the first runnings
+    assert!((plan.retained - 3.63 * system.grain_absorption).abs() < 1e-3);
+    assert!((plan.runnings[0].volume - (10.0 - plan.retained)).abs() < 1e-3);
+
+    recipe.set_system(System::braumeister());
+    let full_volume = recipe.lauter_plan(&SpargeParams::default()).unwrap();
+    assert_eq!(full_volume.method, Method::NoSparge);
+    assert!((full_volume.mash_water - (20.82 + 3.63 * 0.8)).abs() < 1e-3);
+    assert!(full_volume.lauter_efficiency < plan.lauter_efficiency);
+}
+
+#[test]
+fn systems_of_converted_recipes() {
+    let mut json: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string("tests/brewfather/Brewfather_RECIPE_Goedar_20200215.json").unwrap(),
+    )
+    .unwrap();
+    json["equipment"]["mashTunDeadSpace"] = 1.5.into();
+    json["equipment"]["grainAbsorptionRate"] = 0.8.into();
+    let recipe: BrewfatherRecipe = serde_json::from_value(json).unwrap();
+    let system = Recipe::from(recipe).system();
+    assert_eq!(system.kind, Kind::Traditional);
+    assert_eq!(
+        (system.lauter_deadspace, system.grain_absorption),
+        (1.5, 0.8)
+    );
+
+    // Mashed in the brew kettle
+    let recipe = load::load("tests/beerjson/dry_stout.json")
+        .unwrap()
+        .remove(0);
+    let system = recipe.system();
+    assert_eq!(system.kind, Kind::BrewInABag);
+    assert!((system.grain_absorption - 0.522).abs() < 1e-3);
+    assert!((system.mash_volume.unwrap() - 37.854).abs() < 1e-3);
+    assert_eq!(system.lauter_deadspace, 0.0);
+}
@@ -1,2 +1,4 @@
+mod beerjson;
 mod beerxml;
 mod brewfather;
+mod bryggio;